@@ -0,0 +1,578 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use std::fmt::Write as _;
+
+/// Shipped default bindings; never written by the game.
+pub const INPUT_CONFIG_PATH: &str = "config/input.cfg";
+/// The player's rebinds, layered over [`INPUT_CONFIG_PATH`]. Not tracked in git.
+pub const INPUT_OVERRIDES_PATH: &str = "config/input.user.cfg";
+
+/// Everything the player can do, independent of which device button triggers it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Attack,
+    Block,
+    Dodge,
+    Jump,
+    Sprint,
+    Crouch,
+    LockOn,
+    Interact,
+    Inventory,
+    Heal,
+    CompanionFollow,
+    CompanionHold,
+    CompanionAttack,
+    ToggleCamera,
+}
+
+impl Action {
+    pub const ALL: [Action; 18] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Attack,
+        Action::Block,
+        Action::Dodge,
+        Action::Jump,
+        Action::Sprint,
+        Action::Crouch,
+        Action::LockOn,
+        Action::Interact,
+        Action::Inventory,
+        Action::Heal,
+        Action::CompanionFollow,
+        Action::CompanionHold,
+        Action::CompanionAttack,
+        Action::ToggleCamera,
+    ];
+
+    pub fn name(self) -> String {
+        format!("{self:?}")
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Fixed menu controls. Unlike [`Action`]s they aren't rebindable, so they can
+/// share buttons with gameplay actions (South is also Jump).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    Confirm,
+    /// A menu's second option, like the game-over screen's enemy reset toggle.
+    Alternate,
+    Up,
+    Down,
+    Back,
+}
+
+impl MenuAction {
+    pub fn bindings(self) -> &'static [Binding] {
+        use Binding::{Gamepad, Key};
+        match self {
+            MenuAction::Confirm => &[Key(KeyCode::Enter), Gamepad(GamepadButton::South)],
+            MenuAction::Alternate => &[Key(KeyCode::KeyR), Gamepad(GamepadButton::West)],
+            MenuAction::Up => &[Key(KeyCode::ArrowUp), Gamepad(GamepadButton::DPadUp)],
+            MenuAction::Down => &[Key(KeyCode::ArrowDown), Gamepad(GamepadButton::DPadDown)],
+            MenuAction::Back => &[Key(KeyCode::Escape)],
+        }
+    }
+
+    pub fn just_pressed(self, devices: &InputDevices) -> bool {
+        self.bindings()
+            .iter()
+            .any(|binding| binding.just_pressed(devices))
+    }
+}
+
+/// A physical button an action can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+/// Every device a [`Binding`] can be read from. Gamepad bindings match any
+/// connected pad.
+#[derive(SystemParam)]
+pub struct InputDevices<'w, 's> {
+    pub keyboard: Res<'w, ButtonInput<KeyCode>>,
+    pub mouse: Res<'w, ButtonInput<MouseButton>>,
+    pub gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+/// Keys that can appear in the config file, looked up by their `KeyCode` name.
+#[rustfmt::skip]
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+    KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+    KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+    KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+    KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ArrowLeft, KeyCode::ArrowRight,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight,
+    KeyCode::AltLeft, KeyCode::AltRight, KeyCode::CapsLock,
+    KeyCode::Comma, KeyCode::Period, KeyCode::Semicolon, KeyCode::Quote, KeyCode::Slash,
+    KeyCode::Backslash, KeyCode::BracketLeft, KeyCode::BracketRight, KeyCode::Minus,
+    KeyCode::Equal, KeyCode::Backquote, KeyCode::IntlBackslash,
+    KeyCode::F1, KeyCode::F2, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8,
+    KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+];
+
+const BINDABLE_MOUSE_BUTTONS: &[MouseButton] = &[
+    MouseButton::Left,
+    MouseButton::Right,
+    MouseButton::Middle,
+    MouseButton::Back,
+    MouseButton::Forward,
+];
+
+/// Start is left out: like Escape on the keyboard it always opens the pause menu.
+const BINDABLE_GAMEPAD_BUTTONS: &[GamepadButton] = &[
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::North,
+    GamepadButton::West,
+    GamepadButton::LeftTrigger,
+    GamepadButton::LeftTrigger2,
+    GamepadButton::RightTrigger,
+    GamepadButton::RightTrigger2,
+    GamepadButton::Select,
+    GamepadButton::LeftThumb,
+    GamepadButton::RightThumb,
+    GamepadButton::DPadUp,
+    GamepadButton::DPadDown,
+    GamepadButton::DPadLeft,
+    GamepadButton::DPadRight,
+];
+
+impl Binding {
+    pub fn is_gamepad(self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key) => format!("{key:?}"),
+            Binding::Mouse(button) => format!("Mouse{button:?}"),
+            Binding::Gamepad(button) => format!("Gamepad{button:?}"),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(button) = name.strip_prefix("Mouse") {
+            return BINDABLE_MOUSE_BUTTONS
+                .iter()
+                .find(|candidate| format!("{candidate:?}") == button)
+                .map(|button| Binding::Mouse(*button));
+        }
+        if let Some(button) = name.strip_prefix("Gamepad") {
+            return BINDABLE_GAMEPAD_BUTTONS
+                .iter()
+                .find(|candidate| format!("{candidate:?}") == button)
+                .map(|button| Binding::Gamepad(*button));
+        }
+        BINDABLE_KEYS
+            .iter()
+            .find(|candidate| format!("{candidate:?}") == name)
+            .map(|key| Binding::Key(*key))
+    }
+
+    pub fn pressed(self, devices: &InputDevices) -> bool {
+        match self {
+            Binding::Key(key) => devices.keyboard.pressed(key),
+            Binding::Mouse(button) => devices.mouse.pressed(button),
+            Binding::Gamepad(button) => devices
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.pressed(button)),
+        }
+    }
+
+    pub fn just_pressed(self, devices: &InputDevices) -> bool {
+        match self {
+            Binding::Key(key) => devices.keyboard.just_pressed(key),
+            Binding::Mouse(button) => devices.mouse.just_pressed(button),
+            Binding::Gamepad(button) => devices
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(button)),
+        }
+    }
+
+    /// The first bindable button pressed this frame, for "press a key" rebinding.
+    pub fn first_just_pressed(devices: &InputDevices) -> Option<Self> {
+        let key = devices
+            .keyboard
+            .get_just_pressed()
+            .find(|key| BINDABLE_KEYS.contains(key))
+            .map(|key| Binding::Key(*key));
+        let mouse = || {
+            devices
+                .mouse
+                .get_just_pressed()
+                .find(|button| BINDABLE_MOUSE_BUTTONS.contains(button))
+                .map(|button| Binding::Mouse(*button))
+        };
+        let gamepad = || {
+            devices.gamepads.iter().find_map(|gamepad| {
+                gamepad
+                    .get_just_pressed()
+                    .find(|button| BINDABLE_GAMEPAD_BUTTONS.contains(button))
+                    .map(|button| Binding::Gamepad(*button))
+            })
+        };
+        key.or_else(mouse).or_else(gamepad)
+    }
+}
+
+/// Action -> button map. Loaded from [`INPUT_CONFIG_PATH`] and then
+/// [`INPUT_OVERRIDES_PATH`] at startup; changes are saved to the overrides file.
+#[derive(Resource, Clone)]
+pub struct InputBindings {
+    pub bindings: HashMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Binding::{Gamepad, Key, Mouse};
+
+        let bindings = [
+            (
+                Action::MoveForward,
+                vec![Key(KeyCode::KeyW), Key(KeyCode::ArrowUp)],
+            ),
+            (
+                Action::MoveBack,
+                vec![Key(KeyCode::KeyS), Key(KeyCode::ArrowDown)],
+            ),
+            (
+                Action::MoveLeft,
+                vec![Key(KeyCode::KeyA), Key(KeyCode::ArrowLeft)],
+            ),
+            (
+                Action::MoveRight,
+                vec![Key(KeyCode::KeyD), Key(KeyCode::ArrowRight)],
+            ),
+            (
+                Action::Attack,
+                vec![
+                    Mouse(MouseButton::Left),
+                    Gamepad(GamepadButton::RightTrigger),
+                ],
+            ),
+            (
+                Action::Block,
+                vec![
+                    Mouse(MouseButton::Right),
+                    Gamepad(GamepadButton::LeftTrigger),
+                ],
+            ),
+            (
+                Action::Dodge,
+                vec![Key(KeyCode::KeyQ), Gamepad(GamepadButton::East)],
+            ),
+            (
+                Action::Jump,
+                vec![Key(KeyCode::Space), Gamepad(GamepadButton::South)],
+            ),
+            (
+                Action::Sprint,
+                vec![
+                    Key(KeyCode::ShiftLeft),
+                    Key(KeyCode::ShiftRight),
+                    Gamepad(GamepadButton::LeftThumb),
+                ],
+            ),
+            (
+                Action::Crouch,
+                vec![Key(KeyCode::KeyC), Gamepad(GamepadButton::DPadLeft)],
+            ),
+            (
+                Action::LockOn,
+                vec![
+                    Key(KeyCode::Tab),
+                    Mouse(MouseButton::Middle),
+                    Gamepad(GamepadButton::RightThumb),
+                ],
+            ),
+            (
+                Action::Interact,
+                vec![Key(KeyCode::KeyE), Gamepad(GamepadButton::West)],
+            ),
+            (
+                Action::Inventory,
+                vec![Key(KeyCode::KeyI), Gamepad(GamepadButton::North)],
+            ),
+            (
+                Action::Heal,
+                vec![Key(KeyCode::KeyR), Gamepad(GamepadButton::LeftTrigger2)],
+            ),
+            (
+                Action::CompanionFollow,
+                vec![Key(KeyCode::Digit1), Gamepad(GamepadButton::DPadUp)],
+            ),
+            (
+                Action::CompanionHold,
+                vec![Key(KeyCode::Digit2), Gamepad(GamepadButton::DPadDown)],
+            ),
+            (
+                Action::CompanionAttack,
+                vec![Key(KeyCode::Digit3), Gamepad(GamepadButton::DPadRight)],
+            ),
+            (
+                Action::ToggleCamera,
+                vec![Key(KeyCode::KeyV), Gamepad(GamepadButton::Select)],
+            ),
+        ];
+
+        Self {
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn pressed(&self, action: Action, devices: &InputDevices) -> bool {
+        self.get(action)
+            .iter()
+            .any(|binding| binding.pressed(devices))
+    }
+
+    pub fn just_pressed(&self, action: Action, devices: &InputDevices) -> bool {
+        self.get(action)
+            .iter()
+            .any(|binding| binding.just_pressed(devices))
+    }
+
+    /// Makes `binding` the only button of its kind (keyboard and mouse, or gamepad)
+    /// for `action`, taking it away from any other action. Bindings on the other
+    /// kind of device are kept, so rebinding a key doesn't unbind the gamepad.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        for bindings in self.bindings.values_mut() {
+            bindings.retain(|existing| *existing != binding);
+        }
+        let bindings = self.bindings.entry(action).or_default();
+        let slot = bindings
+            .iter()
+            .position(|existing| existing.is_gamepad() == binding.is_gamepad())
+            .unwrap_or(bindings.len());
+        bindings.retain(|existing| existing.is_gamepad() != binding.is_gamepad());
+        bindings.insert(slot, binding);
+    }
+
+    /// Parses `Action = Binding, Binding` lines; `#` starts a comment. Actions missing
+    /// from the file keep their default bindings.
+    pub fn parse(config: &str) -> Self {
+        let mut bindings = Self::default();
+        bindings.merge(config, INPUT_CONFIG_PATH);
+        bindings
+    }
+
+    /// Applies the actions listed in `config` on top of the current bindings.
+    /// `path` is only used in warnings.
+    fn merge(&mut self, config: &str, path: &str) {
+        for (line_number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((action_name, binding_names)) = line.split_once('=') else {
+                warn!("{path}:{}: expected `Action = Key`", line_number + 1);
+                continue;
+            };
+            let Some(action) = Action::from_name(action_name.trim()) else {
+                warn!(
+                    "{path}:{}: unknown action '{}'",
+                    line_number + 1,
+                    action_name.trim()
+                );
+                continue;
+            };
+
+            let mut action_bindings = Vec::new();
+            for name in binding_names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                match Binding::from_name(name) {
+                    Some(binding) => action_bindings.push(binding),
+                    None => warn!("{path}:{}: unknown key '{name}'", line_number + 1),
+                }
+            }
+            self.bindings.insert(action, action_bindings);
+        }
+    }
+
+    pub fn to_config(&self) -> String {
+        let mut config = String::from(
+            "# Action = Key[, Key...]  (mouse: MouseLeft, ...; gamepad: GamepadSouth, ...)\n",
+        );
+        for action in Action::ALL {
+            let names: Vec<String> = self
+                .get(action)
+                .iter()
+                .map(|binding| binding.name())
+                .collect();
+            let _ = writeln!(config, "{} = {}", action.name(), names.join(", "));
+        }
+        config
+    }
+
+    /// The shipped bindings from [`INPUT_CONFIG_PATH`], ignoring the player's rebinds.
+    pub fn load_defaults() -> Self {
+        match std::fs::read_to_string(INPUT_CONFIG_PATH) {
+            Ok(config) => {
+                info!("Loaded input bindings from {INPUT_CONFIG_PATH}");
+                Self::parse(&config)
+            }
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn load() -> Self {
+        let mut bindings = Self::load_defaults();
+        if let Ok(config) = std::fs::read_to_string(INPUT_OVERRIDES_PATH) {
+            info!("Loaded rebinds from {INPUT_OVERRIDES_PATH}");
+            bindings.merge(&config, INPUT_OVERRIDES_PATH);
+        }
+        bindings
+    }
+
+    /// Writes the current bindings to [`INPUT_OVERRIDES_PATH`].
+    pub fn save(&self) {
+        let path = std::path::Path::new(INPUT_OVERRIDES_PATH);
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(err) = std::fs::write(path, self.to_config()) {
+            warn!("Couldn't save input bindings to {INPUT_OVERRIDES_PATH}: {err}");
+        }
+    }
+
+    /// Drops the player's rebinds and goes back to the shipped bindings.
+    pub fn reset() -> Self {
+        match std::fs::remove_file(INPUT_OVERRIDES_PATH) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => warn!("Couldn't remove {INPUT_OVERRIDES_PATH}: {err}"),
+        }
+        Self::load_defaults()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Binding::{Gamepad, Key, Mouse};
+
+    #[test]
+    fn rebinding_a_key_keeps_the_gamepad_binding() {
+        let mut bindings = InputBindings::default();
+        bindings.rebind(Action::Sprint, Key(KeyCode::KeyB));
+        assert_eq!(
+            bindings.get(Action::Sprint),
+            [Key(KeyCode::KeyB), Gamepad(GamepadButton::LeftThumb)]
+        );
+
+        bindings.rebind(Action::Attack, Key(KeyCode::KeyF));
+        assert_eq!(
+            bindings.get(Action::Attack),
+            [Key(KeyCode::KeyF), Gamepad(GamepadButton::RightTrigger)]
+        );
+    }
+
+    #[test]
+    fn rebinding_a_gamepad_button_keeps_the_keys() {
+        let mut bindings = InputBindings::default();
+        bindings.rebind(Action::Dodge, Gamepad(GamepadButton::LeftThumb));
+        assert_eq!(
+            bindings.get(Action::Dodge),
+            [Key(KeyCode::KeyQ), Gamepad(GamepadButton::LeftThumb)]
+        );
+        // Taken away from Sprint, which still has its keys
+        assert_eq!(
+            bindings.get(Action::Sprint),
+            [Key(KeyCode::ShiftLeft), Key(KeyCode::ShiftRight)]
+        );
+    }
+
+    #[test]
+    fn rebinding_adds_a_binding_to_an_unbound_action() {
+        let mut bindings = InputBindings::default();
+        bindings.bindings.remove(&Action::Heal);
+        bindings.rebind(Action::Heal, Mouse(MouseButton::Back));
+        assert_eq!(bindings.get(Action::Heal), [Mouse(MouseButton::Back)]);
+    }
+
+    #[test]
+    fn parse_replaces_listed_actions_and_keeps_the_rest() {
+        let bindings = InputBindings::parse(
+            "# comment\n\
+             Jump = KeyF, GamepadNorth # trailing comment\n\
+             Attack = MouseRight\n\
+             Block =\n",
+        );
+        assert_eq!(
+            bindings.get(Action::Jump),
+            [Key(KeyCode::KeyF), Gamepad(GamepadButton::North)]
+        );
+        assert_eq!(bindings.get(Action::Attack), [Mouse(MouseButton::Right)]);
+        assert_eq!(bindings.get(Action::Block), []);
+        assert_eq!(
+            bindings.get(Action::Dodge),
+            InputBindings::default().get(Action::Dodge)
+        );
+    }
+
+    #[test]
+    fn parse_skips_bad_lines_and_unknown_names() {
+        let bindings = InputBindings::parse(
+            "Jump KeyF\n\
+             Fly = KeyF\n\
+             Dodge = KeyX, Nope, GamepadStart\n",
+        );
+        assert_eq!(
+            bindings.get(Action::Jump),
+            InputBindings::default().get(Action::Jump)
+        );
+        assert_eq!(bindings.get(Action::Dodge), [Key(KeyCode::KeyX)]);
+    }
+
+    #[test]
+    fn merge_layers_overrides_over_the_current_bindings() {
+        let mut bindings = InputBindings::parse("Jump = KeyF\n");
+        bindings.merge("Dodge = KeyG\n", INPUT_OVERRIDES_PATH);
+        assert_eq!(bindings.get(Action::Jump), [Key(KeyCode::KeyF)]);
+        assert_eq!(bindings.get(Action::Dodge), [Key(KeyCode::KeyG)]);
+    }
+
+    #[test]
+    fn config_round_trips() {
+        let mut bindings = InputBindings::default();
+        bindings.rebind(Action::Crouch, Gamepad(GamepadButton::LeftTrigger2));
+        let parsed = InputBindings::parse(&bindings.to_config());
+        for action in Action::ALL {
+            assert_eq!(parsed.get(action), bindings.get(action), "{action:?}");
+        }
+    }
+}
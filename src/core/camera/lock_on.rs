@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+
+use super::PlayerYaw;
+use crate::core::input::PlayerInput;
+use crate::gameplay::ai::Enemy;
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::player::Player;
+
+/// Furthest an enemy can be to be picked by the LockOn action.
+pub const LOCK_ON_RANGE: f32 = 2.5;
+/// The lock breaks once the target gets this far away.
+pub const LOCK_ON_BREAK_RANGE: f32 = 3.5;
+/// How quickly the camera swings round to keep the target in view.
+pub const LOCK_ON_TURN_SPEED: f32 = 8.0;
+
+/// Enemy the camera is locked on to, if any.
+#[derive(Resource, Default)]
+pub struct LockOnTarget(pub Option<Entity>);
+
+/// The LockOn action locks on to the nearest living enemy in range, or releases
+/// the current target.
+pub fn toggle_lock_on(
+    input: Res<PlayerInput>,
+    mut target: ResMut<LockOnTarget>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(Entity, &Transform, &CombatStatus), With<Enemy>>,
+) {
+    if !input.lock_on {
+        return;
+    }
+    if target.0.take().is_some() {
+        info!("Lock-on released");
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation;
+
+    target.0 = enemy_query
+        .iter()
+        .filter(|(_, _, combat_status)| !combat_status.is_dead)
+        .map(|(entity, transform, _)| (entity, transform.translation.distance(player_pos)))
+        .filter(|(_, distance)| *distance < LOCK_ON_RANGE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity);
+    match target.0 {
+        Some(entity) => info!("Locked on to {entity}"),
+        None => info!("Nothing to lock on to"),
+    }
+}
+
+/// Drops a target that died or got away, otherwise swings the camera round so
+/// it looks past the player at the target.
+pub fn follow_lock_on(
+    time: Res<Time>,
+    mut target: ResMut<LockOnTarget>,
+    mut yaw: ResMut<PlayerYaw>,
+    player_query: Query<(&Transform, &CombatStatus), With<Player>>,
+    enemy_query: Query<(&Transform, &CombatStatus), With<Enemy>>,
+) {
+    let Some(entity) = target.0 else {
+        return;
+    };
+    let Ok((player_transform, player_status)) = player_query.get_single() else {
+        return;
+    };
+    let to_target = enemy_query
+        .get(entity)
+        .ok()
+        .filter(|(_, combat_status)| !combat_status.is_dead && !player_status.is_dead)
+        .map(|(transform, _)| (transform.translation - player_transform.translation).with_y(0.0))
+        .filter(|offset| offset.length() < LOCK_ON_BREAK_RANGE);
+    let Some(to_target) = to_target else {
+        info!("Lock-on lost");
+        target.0 = None;
+        return;
+    };
+    if to_target.length_squared() < 0.0001 {
+        return;
+    }
+
+    // The camera sits at (sin yaw, cos yaw) from the player, so put it opposite the target
+    let desired = (-to_target.x).atan2(-to_target.z);
+    let diff = (desired - yaw.0 + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+        - std::f32::consts::PI;
+    yaw.0 += diff * (LOCK_ON_TURN_SPEED * time.delta_secs()).min(1.0);
+}
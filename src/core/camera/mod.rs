@@ -2,18 +2,38 @@ use bevy::prelude::*;
 
 mod collision;
 mod follow;
+mod lock_on;
 
 pub use collision::*;
 pub use follow::*;
+pub use lock_on::*;
 
 use crate::core::input::PlayerInput;
+use crate::states::AppState;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerYaw>()
-            .add_systems(Update, (mouse_look_system, camera_follow_with_collision));
+            .init_resource::<CameraMode>()
+            .init_resource::<LockOnTarget>()
+            .add_systems(
+                Update,
+                (
+                    // PlayerInput isn't read while paused, so its last camera_delta
+                    // would otherwise keep turning the camera behind the menu
+                    (
+                        (toggle_camera_mode, toggle_lock_on),
+                        follow_lock_on,
+                        mouse_look_system,
+                    )
+                        .chain()
+                        .run_if(in_state(AppState::Playing)),
+                    camera_follow_with_collision,
+                )
+                    .chain(),
+            );
     }
 }
 
@@ -23,10 +43,39 @@ pub struct FollowCamera;
 #[derive(Resource, Default)]
 pub struct PlayerYaw(pub f32);
 
+/// How the character's facing relates to the camera.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraMode {
+    /// The character always faces where the camera looks and strafes sideways.
+    #[default]
+    Locked,
+    /// The camera orbits freely and the character turns towards where it's moving,
+    /// strafing only while blocking or locked on.
+    Free,
+}
+
+fn toggle_camera_mode(input: Res<PlayerInput>, mut mode: ResMut<CameraMode>) {
+    if input.toggle_camera {
+        *mode = match *mode {
+            CameraMode::Locked => CameraMode::Free,
+            CameraMode::Free => CameraMode::Locked,
+        };
+        info!("Camera mode: {:?}", *mode);
+    }
+}
+
 fn mouse_look_system(
     input: Res<PlayerInput>,
+    mode: Res<CameraMode>,
+    lock_on: Res<LockOnTarget>,
     yaw: ResMut<PlayerYaw>,
     player_query: Query<&mut Transform, With<crate::gameplay::player::Player>>,
 ) {
-    mouse_look(yaw, player_query, input.camera_delta);
+    // While locked on, follow_lock_on steers the camera instead
+    let camera_delta = if lock_on.0.is_some() {
+        Vec2::ZERO
+    } else {
+        input.camera_delta
+    };
+    mouse_look(yaw, player_query, camera_delta, *mode);
 }
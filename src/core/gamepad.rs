@@ -0,0 +1,305 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+
+use crate::states::AppState;
+
+pub const GAMEPAD_CONFIG_PATH: &str = "config/gamepad.cfg";
+
+/// Stick shaping for gamepad play. Loaded from [`GAMEPAD_CONFIG_PATH`] as
+/// `name = value` lines, falling back to these defaults.
+#[derive(Resource, Clone, Debug)]
+pub struct GamepadTuning {
+    /// Left stick deflection below this is ignored.
+    pub move_dead_zone: f32,
+    /// Right stick deflection below this is ignored.
+    pub look_dead_zone: f32,
+    /// Deflection above this counts as fully pushed, so worn sticks still reach 1.0.
+    pub outer_dead_zone: f32,
+    /// Left stick deflection (after the dead zone) at which walking becomes running.
+    pub run_deflection: f32,
+    /// Right stick response curve: 1.0 is linear, higher gives finer control near the centre.
+    pub look_curve: f32,
+    /// Camera turn rate at full right stick deflection, in radians per second.
+    pub look_speed: f32,
+    pub invert_look_x: bool,
+}
+
+impl Default for GamepadTuning {
+    fn default() -> Self {
+        Self {
+            move_dead_zone: 0.15,
+            look_dead_zone: 0.1,
+            outer_dead_zone: 0.95,
+            run_deflection: 0.8,
+            look_curve: 2.0,
+            look_speed: 3.0,
+            invert_look_x: false,
+        }
+    }
+}
+
+impl GamepadTuning {
+    pub fn parse(config: &str) -> Self {
+        let mut tuning = Self::default();
+        for (line_number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                warn!(
+                    "{GAMEPAD_CONFIG_PATH}:{}: expected `name = value`",
+                    line_number + 1
+                );
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+
+            if name == "invert_look_x" {
+                match value.parse() {
+                    Ok(invert) => tuning.invert_look_x = invert,
+                    Err(_) => warn!(
+                        "{GAMEPAD_CONFIG_PATH}:{}: expected true/false",
+                        line_number + 1
+                    ),
+                }
+                continue;
+            }
+
+            let field = match name {
+                "move_dead_zone" => &mut tuning.move_dead_zone,
+                "look_dead_zone" => &mut tuning.look_dead_zone,
+                "outer_dead_zone" => &mut tuning.outer_dead_zone,
+                "run_deflection" => &mut tuning.run_deflection,
+                "look_curve" => &mut tuning.look_curve,
+                "look_speed" => &mut tuning.look_speed,
+                _ => {
+                    warn!(
+                        "{GAMEPAD_CONFIG_PATH}:{}: unknown setting '{name}'",
+                        line_number + 1
+                    );
+                    continue;
+                }
+            };
+            match value.parse() {
+                Ok(value) => *field = value,
+                Err(_) => warn!(
+                    "{GAMEPAD_CONFIG_PATH}:{}: '{value}' is not a number",
+                    line_number + 1
+                ),
+            }
+        }
+        tuning
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(GAMEPAD_CONFIG_PATH) {
+            Ok(config) => Self::parse(&config),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Left stick as a movement vector: dead zones applied, length 0..=1.
+    pub fn shape_move(&self, stick: Vec2) -> Vec2 {
+        radial_dead_zone(stick, self.move_dead_zone, self.outer_dead_zone)
+    }
+
+    /// Right stick as a turn rate in radians per second, after dead zones and the response curve.
+    pub fn shape_look(&self, stick: Vec2) -> Vec2 {
+        let stick = radial_dead_zone(stick, self.look_dead_zone, self.outer_dead_zone);
+        let magnitude = stick.length();
+        if magnitude == 0.0 {
+            return Vec2::ZERO;
+        }
+        let mut rate = stick / magnitude * magnitude.powf(self.look_curve) * self.look_speed;
+        if self.invert_look_x {
+            rate.x = -rate.x;
+        }
+        rate
+    }
+}
+
+/// Rescales `stick` so the range between the two dead zones maps onto 0..=1,
+/// keeping its direction.
+pub fn radial_dead_zone(stick: Vec2, inner: f32, outer: f32) -> Vec2 {
+    let magnitude = stick.length();
+    if magnitude <= inner {
+        return Vec2::ZERO;
+    }
+    let scaled = ((magnitude - inner) / (outer - inner).max(f32::EPSILON)).min(1.0);
+    stick / magnitude * scaled
+}
+
+/// The gamepad stick with the most deflection, so whichever pad is in use drives the player.
+pub fn strongest_stick(gamepads: &Query<&Gamepad>, stick: fn(&Gamepad) -> Vec2) -> Vec2 {
+    gamepads
+        .iter()
+        .map(stick)
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .unwrap_or(Vec2::ZERO)
+}
+
+/// Logs hot-plugging and pauses the game when the last gamepad drops out mid-fight.
+pub fn handle_gamepad_connections(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected { name, .. } => {
+                info!("Gamepad connected: {name}");
+            }
+            GamepadConnection::Disconnected => {
+                let remaining = gamepads.iter().filter(|pad| *pad != event.gamepad).count();
+                info!("Gamepad disconnected ({remaining} left)");
+                if remaining == 0 && *state.get() == AppState::Playing {
+                    next_state.set(AppState::Paused);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::input::PlayerInput;
+    use bevy::input::gamepad::{
+        RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent,
+    };
+    use bevy::state::app::StatesPlugin;
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    const EPSILON: f32 = 1e-5;
+
+    #[test]
+    fn radial_dead_zone_rescales_between_the_zones() {
+        assert_eq!(
+            radial_dead_zone(Vec2::new(0.1, 0.0), 0.15, 0.95),
+            Vec2::ZERO
+        );
+
+        let half = radial_dead_zone(Vec2::new(0.0, 0.55), 0.15, 0.95);
+        assert_eq!(half.x, 0.0);
+        assert!((half.y - 0.5).abs() < EPSILON);
+
+        // Past the outer zone counts as fully pushed, in the same direction
+        let full = radial_dead_zone(Vec2::new(1.0, 1.0), 0.15, 0.95);
+        assert!((full.length() - 1.0).abs() < EPSILON);
+        assert!((full.x - full.y).abs() < EPSILON);
+    }
+
+    #[test]
+    fn shape_look_applies_curve_speed_and_invert() {
+        let tuning = GamepadTuning {
+            look_dead_zone: 0.0,
+            outer_dead_zone: 1.0,
+            look_curve: 2.0,
+            look_speed: 3.0,
+            ..default()
+        };
+        assert_eq!(tuning.shape_look(Vec2::ZERO), Vec2::ZERO);
+        assert!((tuning.shape_look(Vec2::new(0.5, 0.0)).x - 0.75).abs() < EPSILON);
+        assert!((tuning.shape_look(Vec2::new(0.0, -1.0)).y + 3.0).abs() < EPSILON);
+
+        let inverted = GamepadTuning {
+            invert_look_x: true,
+            ..tuning
+        };
+        let rate = inverted.shape_look(Vec2::new(0.5, 0.5));
+        assert!(rate.x < 0.0 && rate.y > 0.0);
+    }
+
+    #[test]
+    fn parse_reads_settings_and_keeps_defaults_for_bad_lines() {
+        let tuning = GamepadTuning::parse(
+            "# comment\n\
+             move_dead_zone = 0.2\n\
+             look_speed = 4 # trailing comment\n\
+             invert_look_x = true\n\
+             look_curve = fast\n\
+             unknown = 1\n\
+             no equals sign\n",
+        );
+        let defaults = GamepadTuning::default();
+        assert_eq!(tuning.move_dead_zone, 0.2);
+        assert_eq!(tuning.look_speed, 4.0);
+        assert!(tuning.invert_look_x);
+        assert_eq!(tuning.look_curve, defaults.look_curve);
+        assert_eq!(tuning.run_deflection, defaults.run_deflection);
+    }
+
+    fn input_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, bevy::input::InputPlugin, StatesPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                0.1,
+            )))
+            .init_state::<AppState>()
+            .add_plugins(crate::core::InputPlugin);
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::Playing);
+        app.update();
+        app
+    }
+
+    fn axis(gamepad: Entity, axis: GamepadAxis, value: f32) -> RawGamepadEvent {
+        RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(gamepad, axis, value))
+    }
+
+    #[test]
+    fn gamepad_drives_player_input() {
+        let mut app = input_app();
+        let pad = app.world_mut().spawn_empty().id();
+        app.world_mut().send_event(GamepadConnectionEvent::new(
+            pad,
+            GamepadConnection::Connected {
+                name: "Test pad".to_string(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+        app.update();
+
+        // Half deflection walks forward at about half speed; the right stick turns the camera
+        app.world_mut().send_event_batch([
+            axis(pad, GamepadAxis::LeftStickY, 0.5),
+            axis(pad, GamepadAxis::RightStickX, 1.0),
+        ]);
+        app.update();
+        let input = app.world().resource::<PlayerInput>();
+        assert!(input.movement.z < -0.3 && input.movement.z > -0.6);
+        assert_eq!(input.movement.x, 0.0);
+        assert!(!input.sprinting);
+        assert!(input.camera_delta.x > 0.0);
+
+        // Pushing the stick all the way runs
+        app.world_mut().send_event_batch([
+            axis(pad, GamepadAxis::LeftStickY, 1.0),
+            axis(pad, GamepadAxis::RightStickX, 0.0),
+        ]);
+        app.update();
+        let input = app.world().resource::<PlayerInput>();
+        assert!((input.movement.z + 1.0).abs() < EPSILON);
+        assert!(input.sprinting);
+        assert_eq!(input.camera_delta, Vec2::ZERO);
+
+        // So does holding the stick click, even standing still
+        app.world_mut().send_event_batch([
+            axis(pad, GamepadAxis::LeftStickY, 0.0),
+            RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+                pad,
+                GamepadButton::LeftThumb,
+                1.0,
+            )),
+        ]);
+        app.update();
+        let input = app.world().resource::<PlayerInput>();
+        assert_eq!(input.movement, Vec3::ZERO);
+        assert!(input.sprinting);
+    }
+}
@@ -1,82 +1,323 @@
-use bevy::{input::mouse::MouseMotion, prelude::*};
+use bevy::{
+    input::{mouse::MouseMotion, InputSystem},
+    prelude::*,
+};
 
+use super::bindings::{Action, InputBindings, InputDevices, MenuAction};
+use super::gamepad::{handle_gamepad_connections, strongest_stick, GamepadTuning};
 use crate::states::AppState;
 
 pub const MOUSE_SENSITIVITY: f32 = 0.003;
 pub const ATTACK_BUFFER_TIME: f32 = 0.15;
+pub const JUMP_BUFFER_TIME: f32 = 0.15;
+pub const DODGE_BUFFER_TIME: f32 = 0.2;
 
-#[derive(Resource, Default)]
+/// Actions remembered for a short window when pressed before they're allowed,
+/// so they fire as soon as the current action ends instead of being dropped.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BufferedAction {
+    Dodge,
+    Jump,
+    Attack,
+}
+
+impl BufferedAction {
+    /// Highest priority first: executing one drops everything queued after it here.
+    pub const BY_PRIORITY: [BufferedAction; 3] = [
+        BufferedAction::Dodge,
+        BufferedAction::Jump,
+        BufferedAction::Attack,
+    ];
+
+    pub fn window(self) -> f32 {
+        match self {
+            BufferedAction::Dodge => DODGE_BUFFER_TIME,
+            BufferedAction::Jump => JUMP_BUFFER_TIME,
+            BufferedAction::Attack => ATTACK_BUFFER_TIME,
+        }
+    }
+}
+
+/// Seconds left before each queued action expires, indexed like
+/// [`BufferedAction::BY_PRIORITY`].
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct InputBuffer {
+    pub timers: [f32; 3],
+}
+
+impl InputBuffer {
+    fn timer(&mut self, action: BufferedAction) -> &mut f32 {
+        &mut self.timers[action as usize]
+    }
+
+    /// Counts down every window, then queues `pressed`. A press while the same
+    /// action is still queued doesn't extend it (prevents spam-queuing).
+    pub fn update(&mut self, pressed: &[BufferedAction], delta: f32) {
+        for action in BufferedAction::BY_PRIORITY {
+            let timer = self.timer(action);
+            if pressed.contains(&action) && *timer <= 0.0 {
+                *timer = action.window();
+            } else if !pressed.contains(&action) {
+                *timer = (*timer - delta).max(0.0);
+            }
+        }
+    }
+
+    pub fn is_queued(&self, action: BufferedAction) -> bool {
+        self.timers[action as usize] > 0.0
+    }
+
+    /// Drops `action` once it executes, along with anything queued at a lower
+    /// priority so that doesn't fire straight after it. Higher-priority actions
+    /// stay queued for whenever they're allowed.
+    pub fn consume(&mut self, action: BufferedAction) {
+        for timer in &mut self.timers[action as usize..] {
+            *timer = 0.0;
+        }
+    }
+
+    /// Drops everything queued.
+    pub fn clear(&mut self) {
+        self.timers = [0.0; 3];
+    }
+}
+
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
 pub struct PlayerInput {
+    /// -z forward, +x right. Length 1 from keys, 0..=1 from an analog stick.
     pub movement: Vec3,
     pub attacking: bool,
     pub blocking: bool,
     pub dodging: bool,
     pub jumping: bool,
     pub sprinting: bool,
+    /// Toggles crouching; pressed this frame.
+    pub crouch: bool,
+    pub lock_on: bool,
+    /// Held, so hold-to-use interactions (like reviving) can read it every frame.
+    pub interacting: bool,
+    /// Interact pressed this frame, for one-shot interactions (doors, levers, pickups).
+    pub interact: bool,
+    /// Drinks from a flask (or the first healing item carried); pressed this frame.
+    pub heal: bool,
+    pub companion_follow: bool,
+    pub companion_hold: bool,
+    pub companion_attack: bool,
+    pub toggle_camera: bool,
+    /// Opens or closes the inventory screen.
+    pub inventory: bool,
+    /// Menu buttons pressed this frame. Menus read these rather than the devices
+    /// so replays can drive them too.
+    pub menu_confirm: bool,
+    pub menu_alternate: bool,
+    pub menu_up: bool,
+    pub menu_down: bool,
+    pub menu_back: bool,
+    /// Menu entry the mouse moved onto or clicked this frame, by index.
+    pub menu_pointed: Option<usize>,
     pub camera_delta: Vec2,
-    pub attack_buffer: f32,
+    pub buffer: InputBuffer,
 }
 
 impl PlayerInput {
-    pub fn attack_buffered(&self) -> bool {
-        self.attack_buffer > 0.0
+    /// True while `action` is queued. Each system checks only its own action, once
+    /// that action is allowed, and then calls [`consume`](Self::consume), so an
+    /// action that can't happen yet never holds up the others.
+    pub fn buffered(&self, action: BufferedAction) -> bool {
+        self.buffer.is_queued(action)
     }
 
-    pub fn consume_attack(&mut self) {
-        self.attack_buffer = 0.0;
+    pub fn consume(&mut self, action: BufferedAction) {
+        self.buffer.consume(action);
+    }
+
+    pub fn consume_buffered(&mut self) {
+        self.buffer.clear();
     }
 }
 
+/// Systems that fill [`PlayerInput`], from the devices or from clicks on menu
+/// buttons. Replays record or overwrite it after this set.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PlayerInputSet;
+
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlayerInput>()
-            .add_systems(Update, read_input.run_if(in_state(AppState::Playing)));
+            .insert_resource(InputBindings::load())
+            .insert_resource(GamepadTuning::load())
+            // PreUpdate so every gameplay system in Update sees this frame's input
+            .add_systems(
+                PreUpdate,
+                (
+                    handle_gamepad_connections.after(InputSystem),
+                    read_input.in_set(PlayerInputSet).after(InputSystem).run_if(
+                        in_state(AppState::Playing)
+                            .or(in_state(AppState::Inventory))
+                            .or(in_state(AppState::GameOver)),
+                    ),
+                ),
+            );
     }
 }
 
-fn read_input(
+pub fn read_input(
     time: Res<Time>,
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    state: Res<State<AppState>>,
+    devices: InputDevices,
+    bindings: Res<InputBindings>,
+    tuning: Res<GamepadTuning>,
     mut mouse_motion: EventReader<MouseMotion>,
     mut input: ResMut<PlayerInput>,
 ) {
+    let pressed = |action| bindings.pressed(action, &devices);
+    let just_pressed = |action| bindings.just_pressed(action, &devices);
+
+    input.inventory = just_pressed(Action::Inventory);
+    input.menu_confirm = MenuAction::Confirm.just_pressed(&devices);
+    input.menu_alternate = MenuAction::Alternate.just_pressed(&devices);
+    input.menu_up = MenuAction::Up.just_pressed(&devices);
+    input.menu_down = MenuAction::Down.just_pressed(&devices);
+    input.menu_back = MenuAction::Back.just_pressed(&devices);
+    // Screens fill this in from their buttons later in `PlayerInputSet`
+    input.menu_pointed = None;
+    if *state.get() != AppState::Playing {
+        // Only menu buttons count in a menu, so nothing pressed there fires once it closes
+        mouse_motion.clear();
+        *input = PlayerInput {
+            inventory: input.inventory,
+            menu_confirm: input.menu_confirm,
+            menu_alternate: input.menu_alternate,
+            menu_up: input.menu_up,
+            menu_down: input.menu_down,
+            menu_back: input.menu_back,
+            buffer: input.buffer.clone(),
+            ..default()
+        };
+        return;
+    }
+
     let mut camera_delta = Vec2::ZERO;
     for event in mouse_motion.read() {
         camera_delta += event.delta;
     }
+    // Right stick turn rate expressed as the mouse motion that would give the same turn
+    let look = tuning.shape_look(strongest_stick(&devices.gamepads, Gamepad::right_stick));
+    camera_delta += look * time.delta_secs() / MOUSE_SENSITIVITY;
     input.camera_delta = camera_delta;
 
     let mut movement = Vec3::ZERO;
-    if keyboard.pressed(KeyCode::KeyW) {
+    if pressed(Action::MoveForward) {
         movement.z -= 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyS) {
+    if pressed(Action::MoveBack) {
         movement.z += 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyA) {
+    if pressed(Action::MoveLeft) {
         movement.x -= 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyD) {
+    if pressed(Action::MoveRight) {
         movement.x += 1.0;
     }
+    let stick = tuning.shape_move(strongest_stick(&devices.gamepads, Gamepad::left_stick));
     if movement != Vec3::ZERO {
         movement = movement.normalize();
+    } else {
+        movement = Vec3::new(stick.x, 0.0, -stick.y);
     }
     input.movement = movement;
 
-    // Buffer attack input - only buffer if not already buffered (prevents spam-queuing)
-    if mouse_button.just_pressed(MouseButton::Left) && input.attack_buffer <= 0.0 {
-        input.attack_buffer = ATTACK_BUFFER_TIME;
-    } else if !mouse_button.just_pressed(MouseButton::Left) {
-        input.attack_buffer = (input.attack_buffer - time.delta_secs()).max(0.0);
+    input.attacking = just_pressed(Action::Attack);
+    input.blocking = pressed(Action::Block);
+    input.jumping = just_pressed(Action::Jump);
+    input.sprinting = pressed(Action::Sprint) || stick.length() >= tuning.run_deflection;
+    input.crouch = just_pressed(Action::Crouch);
+    input.dodging = just_pressed(Action::Dodge);
+    input.lock_on = just_pressed(Action::LockOn);
+    input.interacting = pressed(Action::Interact);
+    input.interact = just_pressed(Action::Interact);
+    input.heal = just_pressed(Action::Heal);
+    input.companion_follow = just_pressed(Action::CompanionFollow);
+    input.companion_hold = just_pressed(Action::CompanionHold);
+    input.companion_attack = just_pressed(Action::CompanionAttack);
+    input.toggle_camera = just_pressed(Action::ToggleCamera);
+
+    let pressed_actions: Vec<BufferedAction> = [
+        (BufferedAction::Dodge, input.dodging),
+        (BufferedAction::Jump, input.jumping),
+        (BufferedAction::Attack, input.attacking),
+    ]
+    .into_iter()
+    .filter_map(|(action, pressed)| pressed.then_some(action))
+    .collect();
+    input.buffer.update(&pressed_actions, time.delta_secs());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BufferedAction::{Attack, Dodge, Jump};
+
+    #[test]
+    fn update_queues_presses_for_their_window() {
+        let mut buffer = InputBuffer::default();
+        buffer.update(&[Dodge, Attack], 0.016);
+        assert_eq!(buffer.timers, [DODGE_BUFFER_TIME, 0.0, ATTACK_BUFFER_TIME]);
+        assert!(buffer.is_queued(Dodge) && buffer.is_queued(Attack));
+        assert!(!buffer.is_queued(Jump));
     }
 
-    input.attacking = mouse_button.just_pressed(MouseButton::Left);
-    input.blocking = mouse_button.pressed(MouseButton::Right);
-    input.jumping = keyboard.just_pressed(KeyCode::Space);
-    input.sprinting = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    input.dodging = keyboard.just_pressed(KeyCode::KeyQ);
+    #[test]
+    fn queued_actions_expire() {
+        let mut buffer = InputBuffer::default();
+        buffer.update(&[Attack], 0.016);
+        buffer.update(&[], ATTACK_BUFFER_TIME * 0.5);
+        assert!(buffer.is_queued(Attack));
+        buffer.update(&[], ATTACK_BUFFER_TIME * 0.5);
+        assert!(!buffer.is_queued(Attack));
+        assert_eq!(buffer.timers, [0.0; 3]);
+    }
+
+    #[test]
+    fn repeated_presses_dont_extend_the_window() {
+        let mut buffer = InputBuffer::default();
+        buffer.update(&[Jump], 0.016);
+        buffer.update(&[], 0.1);
+        buffer.update(&[Jump], 0.016);
+        assert!((buffer.timers[Jump as usize] - (JUMP_BUFFER_TIME - 0.1)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn a_queued_action_that_isnt_allowed_doesnt_hold_up_the_others() {
+        // e.g. a dodge with too little stamina
+        let mut buffer = InputBuffer::default();
+        buffer.update(&[Dodge, Jump, Attack], 0.016);
+        assert!(buffer.is_queued(Attack));
+        buffer.consume(Attack);
+        assert!(!buffer.is_queued(Attack));
+        assert!(buffer.is_queued(Dodge) && buffer.is_queued(Jump));
+    }
+
+    #[test]
+    fn consuming_drops_lower_priorities_only() {
+        let mut buffer = InputBuffer::default();
+        buffer.update(&[Dodge, Jump, Attack], 0.016);
+        buffer.consume(Jump);
+        assert!(buffer.is_queued(Dodge));
+        assert!(!buffer.is_queued(Jump) && !buffer.is_queued(Attack));
+
+        buffer.update(&[Attack], 0.016);
+        buffer.consume(Dodge);
+        assert_eq!(buffer.timers, [0.0; 3]);
+    }
+
+    #[test]
+    fn clear_drops_everything() {
+        let mut buffer = InputBuffer::default();
+        buffer.update(&[Dodge, Jump, Attack], 0.016);
+        buffer.clear();
+        assert_eq!(buffer, InputBuffer::default());
+    }
 }
@@ -1,5 +1,13 @@
 pub mod input;
+pub mod bindings;
 pub mod camera;
+pub mod gamepad;
+pub mod replay;
+pub mod rng;
 
 pub use input::InputPlugin;
+pub use bindings::{Action, Binding, InputBindings, InputDevices};
+pub use gamepad::GamepadTuning;
 pub use camera::CameraPlugin;
+pub use replay::ReplayPlugin;
+pub use rng::RngPlugin;
@@ -0,0 +1,490 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Duration;
+
+use super::input::{InputBuffer, PlayerInput, PlayerInputSet};
+use super::rng::GameRng;
+use crate::gameplay::difficulty::{Difficulty, DifficultySettings};
+use crate::states::AppState;
+
+/// Recording and playback both run on this fixed step so physics and timers
+/// advance identically.
+pub const REPLAY_TIMESTEP: f32 = 1.0 / 60.0;
+const REPLAY_VERSION: u32 = 4;
+
+/// Whether this run is writing a replay, feeding one back, or neither.
+#[derive(Resource, Default)]
+pub enum Replay {
+    #[default]
+    Off,
+    Recording {
+        path: String,
+        writer: Option<BufWriter<File>>,
+        frame: u64,
+    },
+    Playback {
+        path: String,
+        header: ReplayHeader,
+        frames: Vec<PlayerInput>,
+        next: usize,
+    },
+}
+
+/// Everything besides per-frame input that has to match for a replay to play out the same.
+#[derive(Clone, Debug)]
+pub struct ReplayHeader {
+    pub seed: u64,
+    pub difficulty: DifficultySettings,
+    /// Command-line scenario flags (e.g. `--horde`) that change the starting world.
+    pub scenario: Vec<String>,
+}
+
+const SCENARIO_FLAGS: &[&str] = &["--horde"];
+
+fn scenario_from_args() -> Vec<String> {
+    std::env::args()
+        .filter(|arg| SCENARIO_FLAGS.contains(&arg.as_str()))
+        .collect()
+}
+
+/// Reads `--record <path>` / `--replay <path>` from the command line.
+pub fn replay_from_args() -> Replay {
+    let args: Vec<String> = std::env::args().collect();
+    let value_of = |flag: &str| {
+        let position = args.iter().position(|arg| arg == flag)?;
+        args.get(position + 1).cloned()
+    };
+
+    if let Some(path) = value_of("--replay") {
+        match std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|replay| parse_replay(&replay))
+        {
+            Ok((header, frames)) => {
+                return Replay::Playback {
+                    path,
+                    header,
+                    frames,
+                    next: 0,
+                };
+            }
+            Err(err) => warn!("Couldn't load replay {path}: {err}"),
+        }
+    } else if let Some(path) = value_of("--record") {
+        return Replay::Recording {
+            path,
+            writer: None,
+            frame: 0,
+        };
+    }
+    Replay::Off
+}
+
+const FLAG_COUNT: usize = 20;
+
+fn input_flags(input: &PlayerInput) -> [bool; FLAG_COUNT] {
+    [
+        input.attacking,
+        input.blocking,
+        input.dodging,
+        input.jumping,
+        input.sprinting,
+        input.lock_on,
+        input.interacting,
+        input.companion_follow,
+        input.companion_hold,
+        input.companion_attack,
+        input.toggle_camera,
+        input.crouch,
+        input.interact,
+        input.heal,
+        input.menu_confirm,
+        input.menu_alternate,
+        input.inventory,
+        input.menu_up,
+        input.menu_down,
+        input.menu_back,
+    ]
+}
+
+fn write_frame(writer: &mut impl Write, input: &PlayerInput) -> std::io::Result<()> {
+    let flags = input_flags(input)
+        .iter()
+        .enumerate()
+        .fold(0u32, |bits, (bit, set)| bits | (u32::from(*set) << bit));
+    writeln!(
+        writer,
+        "{} {} {} {} {} {} {} {} {}",
+        input.movement.x,
+        input.movement.z,
+        input.camera_delta.x,
+        input.camera_delta.y,
+        input.buffer.timers[0],
+        input.buffer.timers[1],
+        input.buffer.timers[2],
+        flags,
+        input
+            .menu_pointed
+            .map_or("-".to_string(), |index| index.to_string())
+    )
+}
+
+fn parse_frame(line: &str) -> Option<PlayerInput> {
+    let mut fields = line.split_whitespace();
+    let mut float = || fields.next()?.parse::<f32>().ok();
+    let movement = Vec3::new(float()?, 0.0, float()?);
+    let camera_delta = Vec2::new(float()?, float()?);
+    let buffer = InputBuffer {
+        timers: [float()?, float()?, float()?],
+    };
+    let flags: u32 = fields.next()?.parse().ok()?;
+    let menu_pointed = match fields.next()? {
+        "-" => None,
+        index => Some(index.parse().ok()?),
+    };
+    let bit = |index: usize| flags & (1 << index) != 0;
+
+    Some(PlayerInput {
+        movement,
+        attacking: bit(0),
+        blocking: bit(1),
+        dodging: bit(2),
+        jumping: bit(3),
+        sprinting: bit(4),
+        lock_on: bit(5),
+        interacting: bit(6),
+        companion_follow: bit(7),
+        companion_hold: bit(8),
+        companion_attack: bit(9),
+        toggle_camera: bit(10),
+        crouch: bit(11),
+        interact: bit(12),
+        heal: bit(13),
+        menu_confirm: bit(14),
+        menu_alternate: bit(15),
+        inventory: bit(16),
+        menu_up: bit(17),
+        menu_down: bit(18),
+        menu_back: bit(19),
+        menu_pointed,
+        camera_delta,
+        buffer,
+    })
+}
+
+fn write_header(writer: &mut impl Write, header: &ReplayHeader) -> std::io::Result<()> {
+    let difficulty = &header.difficulty;
+    writeln!(writer, "replay {REPLAY_VERSION}")?;
+    writeln!(writer, "seed {}", header.seed)?;
+    writeln!(
+        writer,
+        "difficulty {} {} {} {} {}",
+        difficulty.damage_dealt,
+        difficulty.damage_taken,
+        difficulty.enemy_health,
+        difficulty.reaction_time,
+        difficulty.max_attackers
+    )?;
+    writeln!(writer, "scenario {}", header.scenario.join(" "))?;
+    writeln!(writer, "frames")
+}
+
+/// Parses the header lines, then one [`PlayerInput`] per line after `frames`.
+fn parse_replay(replay: &str) -> Result<(ReplayHeader, Vec<PlayerInput>), String> {
+    let mut lines = replay.lines();
+    if lines.next() != Some(format!("replay {REPLAY_VERSION}").as_str()) {
+        return Err(format!("not a version {REPLAY_VERSION} replay"));
+    }
+
+    let mut header = ReplayHeader {
+        seed: 0,
+        difficulty: DifficultySettings::default(),
+        scenario: Vec::new(),
+    };
+    for line in lines.by_ref() {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "seed" => header.seed = value.parse().map_err(|_| "bad seed")?,
+            "difficulty" => {
+                let values: Vec<f32> = value
+                    .split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| "bad difficulty")?;
+                let [
+                    damage_dealt,
+                    damage_taken,
+                    enemy_health,
+                    reaction_time,
+                    max_attackers,
+                ] = values[..]
+                else {
+                    return Err("bad difficulty".into());
+                };
+                header.difficulty = DifficultySettings {
+                    preset: Difficulty::Custom,
+                    damage_dealt,
+                    damage_taken,
+                    enemy_health,
+                    reaction_time,
+                    max_attackers: max_attackers as usize,
+                };
+            }
+            "scenario" => header.scenario = value.split_whitespace().map(String::from).collect(),
+            "frames" => break,
+            _ => return Err(format!("unknown header line '{line}'")),
+        }
+    }
+
+    let frames = lines
+        .enumerate()
+        .map(|(index, line)| parse_frame(line).ok_or(format!("bad frame {index}")))
+        .collect::<Result<_, _>>()?;
+    Ok((header, frames))
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let replay = replay_from_args();
+        if !matches!(replay, Replay::Off) {
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                REPLAY_TIMESTEP,
+            )));
+        }
+
+        app.insert_resource(replay)
+            .add_systems(PostStartup, start_replay)
+            // The game stays in Loading until everything is in place, so both modes
+            // start on the first frame the world simulates
+            .add_systems(
+                PreUpdate,
+                (record_input, play_back_input)
+                    .after(PlayerInputSet)
+                    .run_if(
+                        in_state(AppState::Playing)
+                            .or(in_state(AppState::Inventory))
+                            .or(in_state(AppState::GameOver)),
+                    ),
+            )
+            .add_systems(OnEnter(AppState::Paused), stop_replay)
+            .add_systems(Last, flush_recording.run_if(on_event::<AppExit>));
+    }
+}
+
+/// Opens the recording file, or restores the recorded seed and difficulty for playback.
+fn start_replay(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    rng: Res<GameRng>,
+    difficulty: Res<DifficultySettings>,
+) {
+    match replay.as_mut() {
+        Replay::Off => {}
+        Replay::Recording { path, writer, .. } => {
+            let header = ReplayHeader {
+                seed: rng.seed(),
+                difficulty: difficulty.clone(),
+                scenario: scenario_from_args(),
+            };
+            let file = File::create(&*path)
+                .map(BufWriter::new)
+                .and_then(|mut file| {
+                    write_header(&mut file, &header)?;
+                    Ok(file)
+                });
+            match file {
+                Ok(file) => {
+                    info!("Recording input to {path}");
+                    *writer = Some(file);
+                }
+                Err(err) => warn!("Couldn't create replay {path}: {err}"),
+            }
+        }
+        Replay::Playback {
+            path,
+            header,
+            frames,
+            ..
+        } => {
+            info!("Playing back {} frames from {path}", frames.len());
+            if header.scenario != scenario_from_args() {
+                warn!(
+                    "Replay was recorded with [{}]; rerun with the same flags for it to match",
+                    header.scenario.join(" ")
+                );
+            }
+            commands.insert_resource(GameRng::new(header.seed));
+            commands.insert_resource(header.difficulty.clone());
+        }
+    }
+}
+
+fn record_input(input: Res<PlayerInput>, mut replay: ResMut<Replay>) {
+    let Replay::Recording {
+        path,
+        writer: Some(writer),
+        frame,
+    } = replay.as_mut()
+    else {
+        return;
+    };
+
+    if let Err(err) = write_frame(writer, &input) {
+        warn!("Stopped recording to {path} at frame {frame}: {err}");
+        *replay = Replay::Off;
+        return;
+    }
+    *frame += 1;
+}
+
+/// Replaces this frame's live input with the recorded one; hands control back
+/// to the devices when the recording runs out.
+fn play_back_input(mut input: ResMut<PlayerInput>, mut replay: ResMut<Replay>) {
+    let Replay::Playback { frames, next, .. } = replay.as_mut() else {
+        return;
+    };
+
+    match frames.get(*next) {
+        Some(frame) => {
+            *input = frame.clone();
+            *next += 1;
+        }
+        None => {
+            info!("Replay finished after {} frames", frames.len());
+            *replay = Replay::Off;
+        }
+    }
+}
+
+/// The pause menu can change the difficulty mid-run, so opening it ends the
+/// recording or playback.
+fn stop_replay(mut replay: ResMut<Replay>, state: Res<State<AppState>>) {
+    match replay.as_mut() {
+        Replay::Off => return,
+        Replay::Recording { path, frame, .. } => {
+            warn!(
+                "Stopped recording to {path} after {frame} frames: {:?} isn't recorded",
+                state.get()
+            );
+            save_recording(&mut replay);
+        }
+        Replay::Playback { path, next, .. } => {
+            warn!(
+                "Stopped playing back {path} at frame {next}: {:?} isn't recorded",
+                state.get()
+            );
+        }
+    }
+    *replay = Replay::Off;
+}
+
+fn flush_recording(mut replay: ResMut<Replay>) {
+    save_recording(&mut replay);
+}
+
+fn save_recording(replay: &mut Replay) {
+    if let Replay::Recording {
+        path,
+        writer: Some(writer),
+        frame,
+    } = replay
+    {
+        match writer.flush() {
+            Ok(()) => info!("Saved {frame} frames to {path}"),
+            Err(err) => warn!("Couldn't save replay {path}: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_input() -> PlayerInput {
+        PlayerInput {
+            movement: Vec3::new(0.25, 0.0, -0.8),
+            attacking: true,
+            dodging: true,
+            sprinting: true,
+            interact: true,
+            heal: true,
+            menu_down: true,
+            menu_pointed: Some(2),
+            camera_delta: Vec2::new(-3.5, 0.125),
+            buffer: InputBuffer {
+                timers: [0.2, 0.0, 0.0333],
+            },
+            ..default()
+        }
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        for input in [PlayerInput::default(), sample_input()] {
+            let mut line = Vec::new();
+            write_frame(&mut line, &input).unwrap();
+            let line = String::from_utf8(line).unwrap();
+            assert_eq!(parse_frame(line.trim_end()), Some(input));
+        }
+    }
+
+    #[test]
+    fn every_flag_survives_a_round_trip() {
+        for bit in 0..FLAG_COUNT {
+            let input = parse_frame(&format!("0 0 0 0 0 0 0 {} -", 1u32 << bit)).unwrap();
+            let flags = input_flags(&input);
+            assert_eq!(flags.iter().filter(|set| **set).count(), 1);
+            assert!(flags[bit]);
+
+            let mut line = Vec::new();
+            write_frame(&mut line, &input).unwrap();
+            assert_eq!(
+                parse_frame(String::from_utf8(line).unwrap().trim_end()),
+                Some(input)
+            );
+        }
+    }
+
+    #[test]
+    fn replays_round_trip() {
+        let header = ReplayHeader {
+            seed: 1234,
+            difficulty: DifficultySettings {
+                preset: Difficulty::Custom,
+                damage_dealt: 1.5,
+                damage_taken: 0.75,
+                enemy_health: 2.0,
+                reaction_time: 0.5,
+                max_attackers: 3,
+            },
+            scenario: vec!["--horde".to_string()],
+        };
+        let frames = vec![sample_input(), PlayerInput::default(), sample_input()];
+
+        let mut replay = Vec::new();
+        write_header(&mut replay, &header).unwrap();
+        for frame in &frames {
+            write_frame(&mut replay, frame).unwrap();
+        }
+        let (parsed_header, parsed_frames) =
+            parse_replay(&String::from_utf8(replay).unwrap()).unwrap();
+
+        assert_eq!(parsed_header.seed, header.seed);
+        assert_eq!(parsed_header.difficulty, header.difficulty);
+        assert_eq!(parsed_header.scenario, header.scenario);
+        assert_eq!(parsed_frames, frames);
+    }
+
+    #[test]
+    fn parse_replay_rejects_bad_input() {
+        assert!(parse_replay("replay 1\nframes\n").is_err());
+        assert!(parse_replay(&format!("replay {REPLAY_VERSION}\nspeed 2\nframes\n")).is_err());
+        assert!(parse_replay(&format!("replay {REPLAY_VERSION}\nframes\n0 0 0\n")).is_err());
+        assert_eq!(parse_frame("0 0 0 0 0 0 0 0 first"), None);
+    }
+}
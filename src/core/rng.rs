@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Independent random streams, so that e.g. spawning more sparks doesn't
+/// change which way the next enemy dodges.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RngStream {
+    Ai,
+    Patrol,
+    Spawner,
+    Particles,
+}
+
+/// Seeded source for all gameplay randomness. Pass `--seed <u64>` to replay a run.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<RngStream, StdRng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::default(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        let seed = self.seed;
+        self.streams.entry(stream).or_insert_with(|| {
+            // Spread the stream ids across the seed space (golden-ratio increment)
+            let offset = (stream as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            StdRng::seed_from_u64(seed ^ offset)
+        })
+    }
+
+    pub fn chance(&mut self, stream: RngStream, probability: f32) -> bool {
+        self.stream(stream).r#gen::<f32>() < probability
+    }
+
+    /// Uniform value in `[0, 1)`.
+    pub fn unit(&mut self, stream: RngStream) -> f32 {
+        self.stream(stream).r#gen()
+    }
+}
+
+/// Reads `--seed <u64>` (or `--seed=<u64>`) from the command line.
+pub fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args();
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--seed") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(String::from),
+            None => continue,
+        };
+        match value.as_deref().map(str::parse) {
+            Some(Ok(seed)) => return Some(seed),
+            _ => warn!("Ignoring invalid --seed argument"),
+        }
+    }
+    None
+}
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = seed_from_args().unwrap_or_else(rand::random);
+        info!("Random seed: {seed} (rerun with --seed {seed} to reproduce)");
+        app.insert_resource(GameRng::new(seed));
+    }
+}
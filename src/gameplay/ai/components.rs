@@ -3,25 +3,102 @@ use bevy::prelude::*;
 pub const ENEMY_WALK_SPEED: f32 = 0.4;
 pub const ENEMY_CHASE_SPEED: f32 = 0.8;
 pub const ENEMY_DETECTION_RANGE: f32 = 1.6;
+/// A crouched player is only seen this close, and only inside the view cone...
+pub const ENEMY_CROUCH_DETECTION_RANGE: f32 = 0.8;
+/// ...and only heard this close outside it.
+pub const ENEMY_HEARING_RANGE: f32 = 0.3;
+/// Cosine of the half-angle of an enemy's view cone.
+pub const ENEMY_VIEW_CONE_COS: f32 = 0.3;
 pub const ENEMY_ATTACK_RANGE: f32 = 0.4;
 pub const ENEMY_PATROL_RANGE: f32 = 0.6;
+pub const ENEMY_CIRCLE_DISTANCE: f32 = 0.9;
+pub const ENEMY_REACTION_TIME: f32 = 0.15;
+pub const ENEMY_BLOCK_CHANCE: f32 = 0.4;
+pub const ENEMY_DODGE_CHANCE: f32 = 0.25;
+pub const ENEMY_MIN_BLOCK_TIME: f32 = 0.5;
+pub const ENEMY_RETREAT_HEALTH_FRACTION: f32 = 0.3;
+pub const ENEMY_RETREAT_STAMINA_FRACTION: f32 = 0.5;
+pub const ENEMY_RETREAT_RECOVERED_FRACTION: f32 = 0.9;
+pub const ENEMY_LEASH_RADIUS: f32 = 3.0;
+pub const ENEMY_REAGGRO_RADIUS: f32 = 2.0;
+pub const ENEMY_HOME_TOLERANCE: f32 = 0.1;
+pub const ENEMY_RESET_HEAL_RATE: f32 = 0.25;
+
+pub const ENEMY_ATTACK_DAMAGE: f32 = 20.0;
+pub const ENEMY_ATTACK_DURATION: f32 = 1.0;
 
 #[derive(Component)]
+#[require(EnemyStats)]
 pub struct Enemy;
 
+/// Per-enemy move set and temperament, read by `enemy_ai` and `combat_system`.
+/// Bosses rewrite this when they change phase.
+#[derive(Component, Clone)]
+pub struct EnemyStats {
+    pub chase_speed: f32,
+    pub attack_damage: f32,
+    pub attack_duration: f32,
+    pub block_chance: f32,
+    pub dodge_chance: f32,
+    pub can_retreat: bool,
+    /// Whether the enemy waits for an `AiDirector` token before attacking.
+    pub uses_attack_tokens: bool,
+    /// Whether the enemy gives up past `ENEMY_LEASH_RADIUS` and heals on the way home.
+    pub leashed: bool,
+}
+
+impl Default for EnemyStats {
+    fn default() -> Self {
+        Self {
+            chase_speed: ENEMY_CHASE_SPEED,
+            attack_damage: ENEMY_ATTACK_DAMAGE,
+            attack_duration: ENEMY_ATTACK_DURATION,
+            block_chance: ENEMY_BLOCK_CHANCE,
+            dodge_chance: ENEMY_DODGE_CHANCE,
+            can_retreat: true,
+            uses_attack_tokens: true,
+            leashed: true,
+        }
+    }
+}
+
 #[derive(Component, Default)]
 pub struct EnemyAi {
     pub state: AiState,
     pub home_position: Vec3,
     pub patrol_target: Option<Vec3>,
     pub state_timer: f32,
+    pub has_attack_token: bool,
+    pub token_cooldown: f32,
+    pub circle_angle: f32,
+    /// Slot around the player this enemy heads for while chasing.
+    pub flank_angle: f32,
+    /// Separation/cohesion force from `update_enemy_steering`, blended into movement.
+    pub steering: Vec3,
+    pub threat_timer: f32,
+    pub reacted_to_attack: bool,
 }
 
-#[derive(Default, Clone, Copy, PartialEq)]
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
 pub enum AiState {
     #[default]
     Idle,
     Patrol,
     Chase,
+    Circle,
     Attack,
+    Block,
+    Dodge,
+    Retreat,
+    Return,
+}
+
+impl AiState {
+    /// Whether the enemy is fighting the player and takes part in attack token handout.
+    pub fn is_engaged(&self) -> bool {
+        matches!(
+            self,
+            AiState::Chase | AiState::Circle | AiState::Attack | AiState::Block | AiState::Dodge
+        )
+    }
 }
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use super::{Enemy, EnemyAi};
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::player::Player;
+
+pub const MAX_SIMULTANEOUS_ATTACKERS: usize = 2;
+pub const ATTACK_TOKEN_COOLDOWN: f32 = 1.5;
+pub const ENEMY_CIRCLE_DRIFT: f32 = 0.3;
+
+/// Coordinates groups of enemies so only a few of them attack the player at once.
+#[derive(Resource)]
+pub struct AiDirector {
+    pub max_attackers: usize,
+}
+
+impl Default for AiDirector {
+    fn default() -> Self {
+        Self {
+            max_attackers: MAX_SIMULTANEOUS_ATTACKERS,
+        }
+    }
+}
+
+struct EngagedEnemy {
+    entity: Entity,
+    angle: f32,
+    distance: f32,
+    has_token: bool,
+    ready: bool,
+}
+
+pub fn update_ai_director(
+    time: Res<Time>,
+    director: Res<AiDirector>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<
+        (Entity, &Transform, &mut EnemyAi, &CombatStatus),
+        (With<Enemy>, Without<Player>),
+    >,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation;
+
+    let mut engaged = Vec::new();
+    for (entity, transform, mut ai, combat_status) in enemy_query.iter_mut() {
+        ai.token_cooldown = (ai.token_cooldown - time.delta_secs()).max(0.0);
+
+        if combat_status.is_dead || !ai.state.is_engaged() {
+            ai.has_attack_token = false;
+            continue;
+        }
+
+        let offset = transform.translation - player_pos;
+        engaged.push(EngagedEnemy {
+            entity,
+            angle: offset.z.atan2(offset.x),
+            distance: Vec2::new(offset.x, offset.z).length(),
+            has_token: ai.has_attack_token,
+            ready: ai.token_cooldown <= 0.0,
+        });
+    }
+
+    // Hand free tokens to the closest enemies that are ready for another attack
+    let holders = engaged.iter().filter(|e| e.has_token).count();
+    let mut candidates: Vec<&mut EngagedEnemy> = engaged
+        .iter_mut()
+        .filter(|e| !e.has_token && e.ready)
+        .collect();
+    candidates.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    for candidate in candidates
+        .into_iter()
+        .take(director.max_attackers.saturating_sub(holders))
+    {
+        candidate.has_token = true;
+        if let Ok((_, _, mut ai, _)) = enemy_query.get_mut(candidate.entity) {
+            ai.has_attack_token = true;
+        }
+    }
+
+    let waiting = engaged
+        .iter()
+        .filter(|e| !e.has_token)
+        .map(|e| (e.entity, e.angle))
+        .collect();
+    for (entity, angle) in spread_evenly(waiting, ENEMY_CIRCLE_DRIFT) {
+        if let Ok((_, _, mut ai, _)) = enemy_query.get_mut(entity) {
+            ai.circle_angle = angle;
+        }
+    }
+}
+
+/// Gives each `(entity, angle around the player)` an evenly spaced slot, starting
+/// `drift` past the first one. Slots keep the enemies' current order so they fan
+/// out without having to cross each other to reach them.
+pub fn spread_evenly(mut members: Vec<(Entity, f32)>, drift: f32) -> Vec<(Entity, f32)> {
+    members.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    let Some(&(_, first)) = members.first() else {
+        return members;
+    };
+    let spacing = std::f32::consts::TAU / members.len() as f32;
+    for (i, (_, angle)) in members.iter_mut().enumerate() {
+        *angle = first + drift + spacing * i as f32;
+    }
+    members
+}
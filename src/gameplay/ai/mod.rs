@@ -1,10 +1,18 @@
 use bevy::prelude::*;
 
 mod components;
+mod director;
+mod patrol;
+mod spawn;
 mod state_machine;
+mod steering;
 
 pub use components::*;
+pub use director::*;
+pub use patrol::*;
+pub use spawn::*;
 pub use state_machine::*;
+pub use steering::*;
 
 use crate::states::AppState;
 
@@ -12,9 +20,18 @@ pub struct AIPlugin;
 
 impl Plugin for AIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            enemy_ai.run_if(in_state(AppState::Playing)),
-        );
+        app.init_resource::<AiDirector>()
+            .insert_resource(PatrolRoutes::load())
+            .add_systems(
+                Update,
+                (
+                    check_assigned_routes,
+                    update_ai_director,
+                    update_enemy_steering,
+                    enemy_ai,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
     }
 }
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::EnemyAi;
+
+pub const PATROL_ROUTES_PATH: &str = "assets/data/patrol_routes.cfg";
+pub const PATROL_WAYPOINT_TOLERANCE: f32 = 0.1;
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum PatrolMode {
+    /// Walk back to the first waypoint after the last one.
+    #[default]
+    Loop,
+    /// Walk the route forwards, then back again.
+    PingPong,
+}
+
+#[derive(Clone)]
+pub struct Waypoint {
+    pub position: Vec3,
+    pub wait_time: f32,
+    /// Knight.glb clip to play while waiting here (e.g. "Interact"); idle if unset.
+    pub animation: Option<String>,
+}
+
+impl Waypoint {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            wait_time: 0.0,
+            animation: None,
+        }
+    }
+
+    pub fn with_wait(mut self, wait_time: f32) -> Self {
+        self.wait_time = wait_time;
+        self
+    }
+
+    pub fn with_animation(mut self, animation: impl Into<String>) -> Self {
+        self.animation = Some(animation.into());
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct PatrolRoute {
+    pub waypoints: Vec<Waypoint>,
+    pub mode: PatrolMode,
+}
+
+/// Named patrol routes authored by the level, looked up by [`AssignedRoute::name`].
+/// Loaded from [`PATROL_ROUTES_PATH`]: a `[name]` header per route followed by
+/// `mode = ...` and `waypoint = x y z [wait [clip]]` lines.
+#[derive(Resource, Default)]
+pub struct PatrolRoutes(pub HashMap<String, PatrolRoute>);
+
+impl PatrolRoutes {
+    pub fn insert(&mut self, name: impl Into<String>, route: PatrolRoute) {
+        self.0.insert(name.into(), route);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PatrolRoute> {
+        self.0.get(name)
+    }
+
+    pub fn parse(config: &str) -> Self {
+        let mut routes = Self::default();
+        let mut current: Option<(String, PatrolRoute)> = None;
+
+        for (line_number, line) in config.lines().enumerate() {
+            let line_number = line_number + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                if let Some((name, route)) = current.take() {
+                    routes.insert(name, route);
+                }
+                current = Some((name.trim().to_string(), PatrolRoute::default()));
+                continue;
+            }
+
+            let Some((name, route)) = current.as_mut() else {
+                warn!("{PATROL_ROUTES_PATH}:{line_number}: expected a `[route_name]` header");
+                continue;
+            };
+            let Some((field, value)) = line.split_once('=') else {
+                warn!("{PATROL_ROUTES_PATH}:{line_number}: expected `name = value`");
+                continue;
+            };
+            match (field.trim(), value.trim()) {
+                ("mode", "loop") => route.mode = PatrolMode::Loop,
+                ("mode", "pingpong") => route.mode = PatrolMode::PingPong,
+                ("waypoint", value) => match parse_waypoint(value) {
+                    Some(waypoint) => route.waypoints.push(waypoint),
+                    None => warn!(
+                        "{PATROL_ROUTES_PATH}:{line_number}: [{name}] expected `waypoint = x y z [wait [clip]]`"
+                    ),
+                },
+                (field, value) => {
+                    warn!("{PATROL_ROUTES_PATH}:{line_number}: [{name}] unknown {field} = {value}")
+                }
+            }
+        }
+        if let Some((name, route)) = current {
+            routes.insert(name, route);
+        }
+        routes
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(PATROL_ROUTES_PATH) {
+            Ok(config) => {
+                let routes = Self::parse(&config);
+                info!(
+                    "Loaded {} patrol routes from {PATROL_ROUTES_PATH}",
+                    routes.0.len()
+                );
+                routes
+            }
+            Err(err) => {
+                warn!("Couldn't read {PATROL_ROUTES_PATH}: {err}");
+                Self::default()
+            }
+        }
+    }
+}
+
+fn parse_waypoint(value: &str) -> Option<Waypoint> {
+    let mut fields = value.split_whitespace();
+    let mut float = || fields.next()?.parse::<f32>().ok();
+    let mut waypoint = Waypoint::new(Vec3::new(float()?, float()?, float()?));
+    if let Some(wait) = fields.next() {
+        waypoint = waypoint.with_wait(wait.parse().ok()?);
+    }
+    if let Some(animation) = fields.next() {
+        waypoint = waypoint.with_animation(animation);
+    }
+    fields.next().is_none().then_some(waypoint)
+}
+
+/// Makes an enemy walk a named route instead of wandering around its home position.
+#[derive(Component)]
+pub struct AssignedRoute {
+    pub name: String,
+    pub index: usize,
+    pub forward: bool,
+    pub wait_timer: f32,
+}
+
+impl AssignedRoute {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            index: 0,
+            forward: true,
+            wait_timer: 0.0,
+        }
+    }
+
+    fn advance(&mut self, route: &PatrolRoute) {
+        let len = route.waypoints.len();
+        if len < 2 {
+            return;
+        }
+        match route.mode {
+            PatrolMode::Loop => self.index = (self.index + 1) % len,
+            PatrolMode::PingPong => {
+                if self.forward && self.index + 1 >= len {
+                    self.forward = false;
+                } else if !self.forward && self.index == 0 {
+                    self.forward = true;
+                }
+                self.index = if self.forward {
+                    self.index + 1
+                } else {
+                    self.index - 1
+                };
+            }
+        }
+    }
+}
+
+/// Warns about enemies given a route the level doesn't define; they'd otherwise
+/// just stand at their spawn point.
+pub fn check_assigned_routes(
+    routes: Res<PatrolRoutes>,
+    assigned_query: Query<(Entity, &AssignedRoute), Added<AssignedRoute>>,
+) {
+    for (entity, assigned) in assigned_query.iter() {
+        if routes.get(&assigned.name).is_none() {
+            warn!(
+                "{entity} was assigned unknown patrol route '{}'",
+                assigned.name
+            );
+        }
+    }
+}
+
+/// Steps an enemy along its route, updating `patrol_target` for the movement code.
+/// Returns the waypoint the enemy is currently waiting at, if any.
+pub fn follow_route<'a>(
+    ai: &mut EnemyAi,
+    assigned: &mut AssignedRoute,
+    route: &'a PatrolRoute,
+    position: Vec3,
+    delta_secs: f32,
+) -> Option<&'a Waypoint> {
+    let waypoint = route.waypoints.get(assigned.index)?;
+
+    if assigned.wait_timer > 0.0 {
+        assigned.wait_timer -= delta_secs;
+        if assigned.wait_timer > 0.0 {
+            return Some(waypoint);
+        }
+        assigned.advance(route);
+    } else {
+        let offset = waypoint.position - position;
+        if Vec2::new(offset.x, offset.z).length() < PATROL_WAYPOINT_TOLERANCE {
+            // The last reached waypoint is where we return to after giving up a chase
+            ai.home_position = waypoint.position;
+            if waypoint.wait_time > 0.0 {
+                assigned.wait_timer = waypoint.wait_time;
+                return Some(waypoint);
+            }
+            assigned.advance(route);
+        }
+    }
+
+    ai.patrol_target = route.waypoints.get(assigned.index).map(|w| w.position);
+    None
+}
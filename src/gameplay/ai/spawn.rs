@@ -0,0 +1,35 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::{Enemy, EnemyAi};
+use crate::gameplay::combat::{CombatStatus, Stamina};
+use crate::gameplay::health::Health;
+use crate::gameplay::player::CHARACTER_SCALE;
+
+/// Spawns a knight enemy guarding `position`. Callers can insert extra components
+/// (`Health`, `EnemyStats`, `AssignedRoute`, ...) on the returned entity.
+pub fn spawn_enemy<'a>(
+    commands: &'a mut Commands,
+    asset_server: &AssetServer,
+    position: Vec3,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        SceneRoot(asset_server.load("models/Knight.glb#Scene0")),
+        Transform::from_translation(position).with_scale(Vec3::splat(CHARACTER_SCALE)),
+        Enemy,
+        EnemyAi {
+            home_position: position,
+            ..default()
+        },
+        Health::default(),
+        Stamina::default(),
+        CombatStatus::default(),
+        RigidBody::KinematicPositionBased,
+        Collider::capsule_y(0.5 * CHARACTER_SCALE, 0.3 * CHARACTER_SCALE),
+        KinematicCharacterController {
+            snap_to_ground: Some(CharacterLength::Absolute(0.1)),
+            ..default()
+        },
+    ))
+}
@@ -2,25 +2,58 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
 use super::{
-    AiState, Enemy, EnemyAi, ENEMY_ATTACK_RANGE, ENEMY_CHASE_SPEED, ENEMY_DETECTION_RANGE,
-    ENEMY_PATROL_RANGE, ENEMY_WALK_SPEED,
+    follow_route, AiState, AssignedRoute, Enemy, EnemyAi, EnemyStats, PatrolRoutes,
+    ATTACK_TOKEN_COOLDOWN, ENEMY_ATTACK_RANGE, ENEMY_CIRCLE_DISTANCE, ENEMY_CROUCH_DETECTION_RANGE,
+    ENEMY_DETECTION_RANGE, ENEMY_FLANK_DISTANCE, ENEMY_HEARING_RANGE, ENEMY_HOME_TOLERANCE,
+    ENEMY_LEASH_RADIUS, ENEMY_MIN_BLOCK_TIME, ENEMY_PATROL_RANGE, ENEMY_REACTION_TIME,
+    ENEMY_REAGGRO_RADIUS, ENEMY_RESET_HEAL_RATE, ENEMY_RETREAT_HEALTH_FRACTION,
+    ENEMY_RETREAT_RECOVERED_FRACTION, ENEMY_RETREAT_STAMINA_FRACTION, ENEMY_VIEW_CONE_COS,
+    ENEMY_WALK_SPEED,
+};
+use crate::core::rng::{GameRng, RngStream};
+use crate::gameplay::combat::{CombatStatus, Stamina, BLOCK_STAMINA_DRAIN, DODGE_STAMINA_COST};
+use crate::gameplay::difficulty::DifficultySettings;
+use crate::gameplay::health::Health;
+use crate::gameplay::player::{
+    Crouching, CurrentAnimation, Dodging, GameAnimations, Player, DODGE_DURATION, DODGE_SPEED,
 };
-use crate::gameplay::combat::CombatStatus;
-use crate::gameplay::player::{CurrentAnimation, GameAnimations, Player};
 
 const GRAVITY: f32 = -20.0;
 
+/// How close the player has to be before an unalerted enemy notices them. A
+/// crouched player has to get nearer, and from behind can only be heard.
+fn detection_range(enemy_rotation: Quat, direction_to_player: Vec3, player_crouching: bool) -> f32 {
+    if !player_crouching {
+        return ENEMY_DETECTION_RANGE;
+    }
+    let forward = enemy_rotation * Vec3::Z;
+    if forward.dot(direction_to_player.with_y(0.0).normalize_or_zero()) > ENEMY_VIEW_CONE_COS {
+        ENEMY_CROUCH_DETECTION_RANGE
+    } else {
+        ENEMY_HEARING_RANGE
+    }
+}
+
 pub fn enemy_ai(
+    mut commands: Commands,
     time: Res<Time>,
     animations: Option<Res<GameAnimations>>,
-    player_query: Query<&Transform, With<Player>>,
+    routes: Res<PatrolRoutes>,
+    mut rng: ResMut<GameRng>,
+    difficulty: Res<DifficultySettings>,
+    player_query: Query<(&Transform, &CombatStatus, Has<Crouching>), With<Player>>,
     mut enemy_query: Query<
         (
             Entity,
             &mut Transform,
             &mut EnemyAi,
             &mut KinematicCharacterController,
-            &CombatStatus,
+            &mut CombatStatus,
+            &mut Stamina,
+            &mut Health,
+            &EnemyStats,
+            Option<&Dodging>,
+            Option<&mut AssignedRoute>,
         ),
         (With<Enemy>, Without<Player>),
     >,
@@ -31,12 +64,23 @@ pub fn enemy_ai(
         return;
     };
 
-    let Ok(player_transform) = player_query.get_single() else {
+    let Ok((player_transform, player_combat, player_crouching)) = player_query.get_single() else {
         return;
     };
     let player_pos = player_transform.translation;
 
-    for (enemy_entity, mut transform, mut ai, mut controller, combat_state) in enemy_query.iter_mut()
+    for (
+        enemy_entity,
+        mut transform,
+        mut ai,
+        mut controller,
+        mut combat_state,
+        mut stamina,
+        mut health,
+        stats,
+        maybe_dodging,
+        mut assigned_route,
+    ) in enemy_query.iter_mut()
     {
         if combat_state.is_dead {
             continue;
@@ -44,58 +88,166 @@ pub fn enemy_ai(
         let enemy_pos = transform.translation;
         let distance_to_player = enemy_pos.distance(player_pos);
         let direction_to_player = (player_pos - enemy_pos).normalize_or_zero();
+        let to_home = Vec3::new(
+            ai.home_position.x - enemy_pos.x,
+            0.0,
+            ai.home_position.z - enemy_pos.z,
+        );
+        let distance_from_home = to_home.length();
+        let noticed = distance_to_player
+            < detection_range(transform.rotation, direction_to_player, player_crouching);
 
         ai.state_timer += time.delta_secs();
 
-        let new_state = match ai.state {
-            AiState::Idle => {
-                if distance_to_player < ENEMY_DETECTION_RANGE {
-                    AiState::Chase
-                } else if ai.state_timer > 3.0 {
-                    ai.state_timer = 0.0;
-                    AiState::Patrol
-                } else {
-                    AiState::Idle
+        // The player's swing reaches a little further than ours (see combat_system)
+        let threatened =
+            player_combat.is_attacking && distance_to_player < ENEMY_ATTACK_RANGE * 1.5;
+        if threatened {
+            ai.threat_timer += time.delta_secs();
+        } else {
+            ai.threat_timer = 0.0;
+            ai.reacted_to_attack = false;
+        }
+
+        let defense = if matches!(ai.state, AiState::Chase | AiState::Circle | AiState::Attack) {
+            choose_defense(
+                &mut ai,
+                stats,
+                &stamina,
+                &health,
+                threatened,
+                ENEMY_REACTION_TIME * difficulty.reaction_time,
+                &mut rng,
+            )
+        } else {
+            None
+        };
+
+        // Give up once dragged too far from home; the player can't kite us across the map
+        let leashed = stats.leashed
+            && matches!(
+                ai.state,
+                AiState::Chase | AiState::Circle | AiState::Attack | AiState::Retreat
+            )
+            && distance_from_home > ENEMY_LEASH_RADIUS;
+
+        let may_attack = ai.has_attack_token || !stats.uses_attack_tokens;
+
+        let new_state = if leashed {
+            ai.state_timer = 0.0;
+            AiState::Return
+        } else if let Some(defensive_state) = defense {
+            ai.state_timer = 0.0;
+            defensive_state
+        } else {
+            match ai.state {
+                AiState::Idle => {
+                    if noticed {
+                        AiState::Chase
+                    } else if ai.state_timer > 3.0 || assigned_route.is_some() {
+                        ai.state_timer = 0.0;
+                        AiState::Patrol
+                    } else {
+                        AiState::Idle
+                    }
                 }
-            }
-            AiState::Patrol => {
-                if distance_to_player < ENEMY_DETECTION_RANGE {
-                    AiState::Chase
-                } else if ai.patrol_target.is_none() || ai.state_timer > 5.0 {
-                    ai.state_timer = 0.0;
-                    AiState::Idle
-                } else {
-                    AiState::Patrol
+                AiState::Patrol => {
+                    if noticed {
+                        AiState::Chase
+                    } else if assigned_route.is_none()
+                        && (ai.patrol_target.is_none() || ai.state_timer > 5.0)
+                    {
+                        ai.state_timer = 0.0;
+                        AiState::Idle
+                    } else {
+                        AiState::Patrol
+                    }
                 }
-            }
-            AiState::Chase => {
-                if distance_to_player < ENEMY_ATTACK_RANGE {
-                    ai.state_timer = 0.0;
-                    AiState::Attack
-                } else if distance_to_player > ENEMY_DETECTION_RANGE * 1.5 {
-                    AiState::Idle
-                } else {
-                    AiState::Chase
+                AiState::Chase => {
+                    if distance_to_player > ENEMY_DETECTION_RANGE * 1.5 {
+                        AiState::Return
+                    } else if !may_attack {
+                        // Without a token, hang back and wait for a turn to attack
+                        if distance_to_player < ENEMY_CIRCLE_DISTANCE {
+                            AiState::Circle
+                        } else {
+                            AiState::Chase
+                        }
+                    } else if distance_to_player < ENEMY_ATTACK_RANGE {
+                        ai.state_timer = 0.0;
+                        AiState::Attack
+                    } else {
+                        AiState::Chase
+                    }
                 }
-            }
-            AiState::Attack => {
-                if ai.state_timer > 1.0 {
-                    ai.state_timer = 0.0;
-                    if distance_to_player < ENEMY_ATTACK_RANGE {
+                AiState::Circle => {
+                    if distance_to_player > ENEMY_DETECTION_RANGE * 1.5 {
+                        AiState::Return
+                    } else if may_attack {
+                        AiState::Chase
+                    } else {
+                        AiState::Circle
+                    }
+                }
+                AiState::Attack => {
+                    if ai.state_timer > stats.attack_duration {
+                        // Give the token back so another enemy gets a turn
+                        ai.state_timer = 0.0;
+                        ai.has_attack_token = false;
+                        ai.token_cooldown = ATTACK_TOKEN_COOLDOWN;
+                        AiState::Chase
+                    } else {
                         AiState::Attack
+                    }
+                }
+                AiState::Block => {
+                    if stamina.current <= 0.0
+                        || (!threatened && ai.state_timer > ENEMY_MIN_BLOCK_TIME)
+                    {
+                        ai.state_timer = 0.0;
+                        AiState::Chase
+                    } else {
+                        AiState::Block
+                    }
+                }
+                AiState::Dodge => {
+                    if ai.state_timer > DODGE_DURATION {
+                        ai.state_timer = 0.0;
+                        AiState::Chase
                     } else {
+                        AiState::Dodge
+                    }
+                }
+                AiState::Retreat => {
+                    if stamina.current >= stamina.max * ENEMY_RETREAT_RECOVERED_FRACTION {
+                        ai.state_timer = 0.0;
                         AiState::Chase
+                    } else {
+                        AiState::Retreat
+                    }
+                }
+                AiState::Return => {
+                    // Only re-engage well inside the leash so we don't ping-pong at the border
+                    if noticed && distance_from_home < ENEMY_REAGGRO_RADIUS {
+                        AiState::Chase
+                    } else if distance_from_home < ENEMY_HOME_TOLERANCE {
+                        health.current = health.max;
+                        ai.state_timer = 0.0;
+                        AiState::Idle
+                    } else {
+                        AiState::Return
                     }
-                } else {
-                    AiState::Attack
                 }
             }
         };
 
         if new_state != ai.state {
+            if ai.state == AiState::Dodge {
+                commands.entity(enemy_entity).remove::<Dodging>();
+            }
             ai.state = new_state;
-            if new_state == AiState::Patrol {
-                let angle = ai.state_timer * 1000.0;
+            if new_state == AiState::Patrol && assigned_route.is_none() {
+                let angle = rng.unit(RngStream::Patrol) * std::f32::consts::TAU;
                 ai.patrol_target = Some(
                     ai.home_position
                         + Vec3::new(
@@ -105,6 +257,28 @@ pub fn enemy_ai(
                         ),
                 );
             }
+            if new_state == AiState::Dodge {
+                // Sidestep to a random side of the incoming swing
+                let side = if rng.chance(RngStream::Ai, 0.5) {
+                    1.0
+                } else {
+                    -1.0
+                };
+                let sidestep = Vec3::new(-direction_to_player.z, 0.0, direction_to_player.x)
+                    .normalize_or_zero()
+                    * side;
+                stamina.current -= DODGE_STAMINA_COST;
+                commands.entity(enemy_entity).insert(Dodging {
+                    direction: sidestep,
+                    timer: DODGE_DURATION,
+                });
+            }
+        }
+
+        combat_state.is_attacking = ai.state == AiState::Attack;
+        combat_state.is_blocking = ai.state == AiState::Block;
+        if combat_state.is_blocking {
+            stamina.current = (stamina.current - BLOCK_STAMINA_DRAIN * time.delta_secs()).max(0.0);
         }
 
         let mut movement = Vec3::ZERO;
@@ -115,21 +289,42 @@ pub fn enemy_ai(
                 desired_anim = Some(animations.idle_index);
             }
             AiState::Patrol => {
-                if let Some(target) = ai.patrol_target {
-                    let dir = (target - enemy_pos).normalize_or_zero();
-                    movement = dir * ENEMY_WALK_SPEED * time.delta_secs();
+                let waiting_at = assigned_route.as_deref_mut().and_then(|assigned| {
+                    let route = routes.get(&assigned.name)?;
+                    follow_route(&mut ai, assigned, route, enemy_pos, time.delta_secs())
+                });
+
+                if let Some(waypoint) = waiting_at {
                     movement.y = GRAVITY * time.delta_secs();
+                    desired_anim = waypoint
+                        .animation
+                        .as_deref()
+                        .and_then(|name| animations.clips.get(name).copied())
+                        .or(Some(animations.idle_index));
+                } else {
+                    if let Some(target) = ai.patrol_target {
+                        let dir = Vec3::new(target.x - enemy_pos.x, 0.0, target.z - enemy_pos.z)
+                            .normalize_or_zero();
+                        movement = dir * ENEMY_WALK_SPEED * time.delta_secs();
+                        movement.y = GRAVITY * time.delta_secs();
 
-                    if dir.length_squared() > 0.01 {
-                        transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                        if dir.length_squared() > 0.01 {
+                            transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                        }
                     }
+                    desired_anim = Some(animations.walk_index);
                 }
-                desired_anim = Some(animations.walk_index);
             }
             AiState::Chase => {
-                let dir =
-                    Vec3::new(direction_to_player.x, 0.0, direction_to_player.z).normalize_or_zero();
-                movement = dir * ENEMY_CHASE_SPEED * time.delta_secs();
+                // Head for our flanking slot rather than straight at the player; the slot
+                // sits inside attack range, so we still end up close enough to swing
+                let slot = player_pos
+                    + Vec3::new(ai.flank_angle.cos(), 0.0, ai.flank_angle.sin())
+                        * ENEMY_FLANK_DISTANCE;
+                let to_slot =
+                    Vec3::new(slot.x - enemy_pos.x, 0.0, slot.z - enemy_pos.z).normalize_or_zero();
+                let dir = (to_slot + ai.steering).normalize_or_zero();
+                movement = dir * stats.chase_speed * time.delta_secs();
                 movement.y = GRAVITY * time.delta_secs();
 
                 if dir.length_squared() > 0.01 {
@@ -137,14 +332,80 @@ pub fn enemy_ai(
                 }
                 desired_anim = Some(animations.run_index);
             }
-            AiState::Attack => {
-                let dir =
-                    Vec3::new(direction_to_player.x, 0.0, direction_to_player.z).normalize_or_zero();
+            AiState::Circle => {
+                let slot = player_pos
+                    + Vec3::new(ai.circle_angle.cos(), 0.0, ai.circle_angle.sin())
+                        * ENEMY_CIRCLE_DISTANCE;
+                let to_slot = Vec3::new(slot.x - enemy_pos.x, 0.0, slot.z - enemy_pos.z);
+                if to_slot.length() > 0.05 {
+                    movement = (to_slot.normalize() + ai.steering).normalize_or_zero()
+                        * ENEMY_WALK_SPEED
+                        * time.delta_secs();
+                    desired_anim = Some(animations.walk_index);
+                } else {
+                    desired_anim = Some(animations.idle_index);
+                }
+                movement.y = GRAVITY * time.delta_secs();
+
+                let dir = Vec3::new(direction_to_player.x, 0.0, direction_to_player.z)
+                    .normalize_or_zero();
+                if dir.length_squared() > 0.01 {
+                    transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                }
+            }
+            AiState::Attack | AiState::Block => {
+                let dir = Vec3::new(direction_to_player.x, 0.0, direction_to_player.z)
+                    .normalize_or_zero();
                 if dir.length_squared() > 0.01 {
                     transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
                 }
                 movement.y = GRAVITY * time.delta_secs();
-                desired_anim = Some(animations.attack_index);
+                desired_anim = Some(if ai.state == AiState::Attack {
+                    animations.attack_index
+                } else {
+                    animations.block_index
+                });
+            }
+            AiState::Dodge => {
+                if let Some(dodging) = maybe_dodging {
+                    movement = dodging.direction * DODGE_SPEED * time.delta_secs();
+                }
+                movement.y = GRAVITY * time.delta_secs();
+                let dodge_right = maybe_dodging.is_some_and(|dodging| {
+                    let right = Vec3::new(-direction_to_player.z, 0.0, direction_to_player.x);
+                    dodging.direction.dot(right) > 0.0
+                });
+                desired_anim = Some(if dodge_right {
+                    animations.dodge_right_index
+                } else {
+                    animations.dodge_left_index
+                });
+            }
+            AiState::Retreat => {
+                let dir = Vec3::new(-direction_to_player.x, 0.0, -direction_to_player.z)
+                    .normalize_or_zero();
+                movement = dir * ENEMY_WALK_SPEED * time.delta_secs();
+                movement.y = GRAVITY * time.delta_secs();
+
+                if dir.length_squared() > 0.01 {
+                    transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                }
+                desired_anim = Some(animations.walk_index);
+            }
+            AiState::Return => {
+                let dir = to_home.normalize_or_zero();
+                movement = dir * ENEMY_WALK_SPEED * time.delta_secs();
+                movement.y = GRAVITY * time.delta_secs();
+
+                if dir.length_squared() > 0.01 {
+                    transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                }
+                if stats.leashed {
+                    health.current = (health.current
+                        + health.max * ENEMY_RESET_HEAL_RATE * time.delta_secs())
+                    .min(health.max);
+                }
+                desired_anim = Some(animations.walk_index);
             }
         }
 
@@ -155,10 +416,12 @@ pub fn enemy_ai(
             .find(|e| anim_query.get(*e).is_ok())
         {
             if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
-                if ai.state == AiState::Attack {
+                if matches!(ai.state, AiState::Attack | AiState::Dodge) {
                     if current_anim.0 != desired_anim {
                         anim_player.stop_all();
-                        anim_player.play(animations.attack_index);
+                        if let Some(anim_index) = desired_anim {
+                            anim_player.play(anim_index);
+                        }
                         current_anim.0 = desired_anim;
                     }
                 } else if current_anim.0 != desired_anim {
@@ -172,3 +435,37 @@ pub fn enemy_ai(
         }
     }
 }
+
+/// Picks a defensive reaction for an engaged enemy, if it wants one this frame.
+/// Badly hurt enemies with little stamina back off; otherwise a player swing gets a
+/// single roll (after the reaction delay) to sidestep or raise a guard.
+fn choose_defense(
+    ai: &mut EnemyAi,
+    stats: &EnemyStats,
+    stamina: &Stamina,
+    health: &Health,
+    threatened: bool,
+    reaction_time: f32,
+    rng: &mut GameRng,
+) -> Option<AiState> {
+    if stats.can_retreat
+        && health.current < health.max * ENEMY_RETREAT_HEALTH_FRACTION
+        && stamina.current < stamina.max * ENEMY_RETREAT_STAMINA_FRACTION
+    {
+        return Some(AiState::Retreat);
+    }
+
+    if !threatened || ai.reacted_to_attack || ai.threat_timer < reaction_time {
+        return None;
+    }
+    ai.reacted_to_attack = true;
+
+    let roll = rng.unit(RngStream::Ai);
+    if roll < stats.dodge_chance && stamina.current >= DODGE_STAMINA_COST {
+        Some(AiState::Dodge)
+    } else if roll < stats.dodge_chance + stats.block_chance && stamina.current > 0.0 {
+        Some(AiState::Block)
+    } else {
+        None
+    }
+}
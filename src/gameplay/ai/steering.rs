@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::{spread_evenly, AiState, Enemy, EnemyAi};
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::player::Player;
+
+/// Radius of the flanking slots chasers head for; inside `ENEMY_ATTACK_RANGE`.
+pub const ENEMY_FLANK_DISTANCE: f32 = 0.3;
+/// Enemies closer than this push each other apart.
+pub const ENEMY_SEPARATION_RADIUS: f32 = 0.35;
+pub const ENEMY_SEPARATION_WEIGHT: f32 = 1.5;
+pub const ENEMY_COHESION_WEIGHT: f32 = 0.2;
+/// Fraction of the requested move below which an enemy counts as blocked by a collider.
+const BLOCKED_FRACTION: f32 = 0.3;
+
+/// Assigns each chasing enemy a flanking slot around the player and computes
+/// separation/cohesion forces that `enemy_ai` blends into its movement.
+pub fn update_enemy_steering(
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut EnemyAi,
+            &CombatStatus,
+            Option<&KinematicCharacterControllerOutput>,
+        ),
+        (With<Enemy>, Without<Player>),
+    >,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation;
+
+    let mut group = Vec::new();
+    let mut chasers = Vec::new();
+    for (entity, transform, ai, combat_status, _) in enemy_query.iter() {
+        if combat_status.is_dead || !matches!(ai.state, AiState::Chase | AiState::Circle) {
+            continue;
+        }
+        let offset = transform.translation - player_pos;
+        group.push((entity, transform.translation));
+        if ai.state == AiState::Chase {
+            chasers.push((entity, offset.z.atan2(offset.x)));
+        }
+    }
+
+    // Spread chasers evenly around the player so they fan out instead of
+    // queueing up on the same line
+    for (entity, angle) in spread_evenly(chasers, 0.0) {
+        if let Ok((_, _, mut ai, _, _)) = enemy_query.get_mut(entity) {
+            ai.flank_angle = angle;
+        }
+    }
+    let centroid = if group.is_empty() {
+        Vec3::ZERO
+    } else {
+        group.iter().map(|(_, position)| *position).sum::<Vec3>() / group.len() as f32
+    };
+
+    for (entity, transform, mut ai, combat_status, output) in enemy_query.iter_mut() {
+        if combat_status.is_dead || !matches!(ai.state, AiState::Chase | AiState::Circle) {
+            ai.steering = Vec3::ZERO;
+            continue;
+        }
+        let position = transform.translation;
+
+        let mut separation = Vec3::ZERO;
+        for (other, other_position) in &group {
+            if *other == entity {
+                continue;
+            }
+            let away = Vec3::new(
+                position.x - other_position.x,
+                0.0,
+                position.z - other_position.z,
+            );
+            let distance = away.length();
+            if distance > 0.001 && distance < ENEMY_SEPARATION_RADIUS {
+                separation += away / distance * (1.0 - distance / ENEMY_SEPARATION_RADIUS);
+            }
+        }
+
+        let cohesion = if group.len() > 1 {
+            Vec3::new(centroid.x - position.x, 0.0, centroid.z - position.z).normalize_or_zero()
+        } else {
+            Vec3::ZERO
+        };
+
+        let mut steering = separation * ENEMY_SEPARATION_WEIGHT + cohesion * ENEMY_COHESION_WEIGHT;
+
+        // If the controller stopped most of last frame's move (another enemy, a
+        // pillar), slide sideways around it rather than pushing into it
+        if let Some(output) = output {
+            let desired = Vec2::new(output.desired_translation.x, output.desired_translation.z);
+            let effective = Vec2::new(
+                output.effective_translation.x,
+                output.effective_translation.z,
+            );
+            if desired.length() > 0.001 && effective.length() < desired.length() * BLOCKED_FRACTION
+            {
+                let sidestep = Vec3::new(-desired.y, 0.0, desired.x).normalize_or_zero();
+                let side = if separation.dot(sidestep) < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                steering += sidestep * side;
+            }
+        }
+
+        ai.steering = steering;
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+
+use crate::gameplay::ai::EnemyStats;
+
+pub const BOSS_SCALE: f32 = 1.5;
+pub const BOSS_ARENA_RADIUS: f32 = 2.5;
+
+/// How hard and how fast the boss hits during a phase.
+#[derive(Clone, Copy)]
+pub struct MoveSet {
+    pub chase_speed: f32,
+    pub attack_damage: f32,
+    pub attack_duration: f32,
+}
+
+/// How the boss fights during a phase.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BossBrain {
+    /// Trades blows and raises its guard often.
+    Duelist,
+    /// Sidesteps rather than blocking.
+    Evasive,
+    /// Never defends, just keeps swinging.
+    Berserker,
+}
+
+impl BossBrain {
+    fn block_chance(&self) -> f32 {
+        match self {
+            BossBrain::Duelist => 0.6,
+            BossBrain::Evasive => 0.2,
+            BossBrain::Berserker => 0.0,
+        }
+    }
+
+    fn dodge_chance(&self) -> f32 {
+        match self {
+            BossBrain::Duelist => 0.1,
+            BossBrain::Evasive => 0.5,
+            BossBrain::Berserker => 0.0,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BossPhase {
+    /// The phase starts once health drops to this fraction of max (1.0 for the opening phase).
+    pub health_threshold: f32,
+    pub move_set: MoveSet,
+    pub brain: BossBrain,
+}
+
+impl BossPhase {
+    pub fn stats(&self) -> EnemyStats {
+        EnemyStats {
+            chase_speed: self.move_set.chase_speed,
+            attack_damage: self.move_set.attack_damage,
+            attack_duration: self.move_set.attack_duration,
+            block_chance: self.brain.block_chance(),
+            dodge_chance: self.brain.dodge_chance(),
+            // Bosses fight alone and to the end
+            can_retreat: false,
+            uses_attack_tokens: false,
+            // The arena holds the fight in place, and a heal would leave the phase behind
+            leashed: false,
+        }
+    }
+}
+
+/// Marks an `Enemy` as a boss. Its `EnemyStats` are driven by the current phase.
+#[derive(Component)]
+pub struct Boss {
+    pub name: String,
+    pub phases: Vec<BossPhase>,
+    pub current_phase: usize,
+}
+
+impl Boss {
+    pub fn new(name: impl Into<String>, phases: Vec<BossPhase>) -> Self {
+        Self {
+            name: name.into(),
+            phases,
+            current_phase: 0,
+        }
+    }
+
+    /// The last phase whose threshold the given health fraction has reached.
+    pub fn phase_for_health(&self, health_fraction: f32) -> usize {
+        self.phases
+            .iter()
+            .rposition(|phase| health_fraction <= phase.health_threshold)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum ArenaState {
+    #[default]
+    Waiting,
+    Active,
+    Cleared,
+}
+
+/// Circular area around its transform; entering it starts the fight with `boss`.
+#[derive(Component)]
+pub struct BossArena {
+    pub boss: Entity,
+    pub radius: f32,
+    pub state: ArenaState,
+}
+
+impl BossArena {
+    pub fn new(boss: Entity) -> Self {
+        Self {
+            boss,
+            radius: BOSS_ARENA_RADIUS,
+            state: ArenaState::Waiting,
+        }
+    }
+}
+
+/// Barrier that blocks an arena exit while the encounter is active.
+#[derive(Component)]
+pub struct ArenaExit {
+    pub arena: Entity,
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::{ArenaExit, ArenaState, Boss, BossArena};
+use crate::gameplay::ai::EnemyStats;
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::health::Health;
+use crate::gameplay::player::Player;
+use crate::gameplay::respawn::PlayerRespawned;
+
+#[derive(Event)]
+pub struct BossEncounterStarted {
+    pub boss: Entity,
+    pub arena: Entity,
+}
+
+#[derive(Event)]
+pub struct BossPhaseChanged {
+    pub boss: Entity,
+    pub phase: usize,
+}
+
+#[derive(Event)]
+pub struct BossDefeated {
+    pub boss: Entity,
+}
+
+/// The player died mid-fight and the arena reopened; the encounter starts over
+/// when they walk back in.
+#[derive(Event)]
+pub struct BossEncounterReset {
+    pub boss: Entity,
+}
+
+pub fn init_boss_stats(mut boss_query: Query<(&Boss, &mut EnemyStats), Added<Boss>>) {
+    for (boss, mut stats) in boss_query.iter_mut() {
+        if let Some(phase) = boss.phases.get(boss.current_phase) {
+            *stats = phase.stats();
+        }
+    }
+}
+
+pub fn update_boss_phase(
+    mut boss_query: Query<(Entity, &mut Boss, &Health, &CombatStatus, &mut EnemyStats)>,
+    mut phase_events: EventWriter<BossPhaseChanged>,
+) {
+    for (entity, mut boss, health, combat_status, mut stats) in boss_query.iter_mut() {
+        if combat_status.is_dead {
+            continue;
+        }
+
+        // Phases only ever advance, even if the boss heals back above a threshold
+        let phase = boss.phase_for_health(health.current / health.max);
+        if phase <= boss.current_phase {
+            continue;
+        }
+
+        boss.current_phase = phase;
+        *stats = boss.phases[phase].stats();
+        info!(
+            "{} enters phase {} ({:?})",
+            boss.name,
+            phase + 1,
+            boss.phases[phase].brain
+        );
+        phase_events.send(BossPhaseChanged {
+            boss: entity,
+            phase,
+        });
+    }
+}
+
+pub fn update_boss_arenas(
+    mut commands: Commands,
+    player_query: Query<&Transform, With<Player>>,
+    mut arena_query: Query<(Entity, &Transform, &mut BossArena)>,
+    boss_query: Query<&CombatStatus, With<Boss>>,
+    mut exit_query: Query<(Entity, &ArenaExit, &mut Visibility)>,
+    mut started_events: EventWriter<BossEncounterStarted>,
+    mut defeated_events: EventWriter<BossDefeated>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (arena_entity, arena_transform, mut arena) in arena_query.iter_mut() {
+        let boss_alive = boss_query
+            .get(arena.boss)
+            .is_ok_and(|combat_status| !combat_status.is_dead);
+
+        let locked = match arena.state {
+            ArenaState::Waiting => {
+                let player_inside =
+                    player_transform.translation.distance(arena_transform.translation)
+                        < arena.radius;
+                if !(player_inside && boss_alive) {
+                    continue;
+                }
+                arena.state = ArenaState::Active;
+                info!("Boss encounter started");
+                started_events.send(BossEncounterStarted {
+                    boss: arena.boss,
+                    arena: arena_entity,
+                });
+                true
+            }
+            ArenaState::Active => {
+                if boss_alive {
+                    continue;
+                }
+                arena.state = ArenaState::Cleared;
+                info!("Boss defeated");
+                defeated_events.send(BossDefeated { boss: arena.boss });
+                false
+            }
+            ArenaState::Cleared => continue,
+        };
+
+        for (exit_entity, exit, mut visibility) in exit_query.iter_mut() {
+            if exit.arena != arena_entity {
+                continue;
+            }
+            if locked {
+                commands.entity(exit_entity).remove::<ColliderDisabled>();
+                *visibility = Visibility::Visible;
+            } else {
+                commands.entity(exit_entity).insert(ColliderDisabled);
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+/// Reopens arenas whose fight ended with the player's death. When enemies are
+/// reset the boss starts over from its first phase too.
+pub fn reset_boss_arenas(
+    mut commands: Commands,
+    mut respawned_events: EventReader<PlayerRespawned>,
+    mut reset_events: EventWriter<BossEncounterReset>,
+    mut arena_query: Query<(Entity, &mut BossArena)>,
+    mut boss_query: Query<(&mut Boss, &mut EnemyStats)>,
+    mut exit_query: Query<(Entity, &ArenaExit, &mut Visibility)>,
+) {
+    for event in respawned_events.read() {
+        for (arena_entity, mut arena) in arena_query.iter_mut() {
+            if arena.state != ArenaState::Active {
+                continue;
+            }
+            arena.state = ArenaState::Waiting;
+            reset_events.send(BossEncounterReset { boss: arena.boss });
+
+            if event.enemies_reset {
+                if let Ok((mut boss, mut stats)) = boss_query.get_mut(arena.boss) {
+                    boss.current_phase = 0;
+                    if let Some(phase) = boss.phases.first() {
+                        *stats = phase.stats();
+                    }
+                }
+            }
+
+            for (exit_entity, exit, mut visibility) in exit_query.iter_mut() {
+                if exit.arena == arena_entity {
+                    commands.entity(exit_entity).insert(ColliderDisabled);
+                    *visibility = Visibility::Hidden;
+                }
+            }
+        }
+    }
+}
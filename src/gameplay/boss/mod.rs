@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+mod components;
+mod encounter;
+
+pub use components::*;
+pub use encounter::*;
+
+use crate::states::AppState;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BossEncounterStarted>()
+            .add_event::<BossPhaseChanged>()
+            .add_event::<BossDefeated>()
+            .add_event::<BossEncounterReset>()
+            .add_systems(
+                Update,
+                (
+                    init_boss_stats,
+                    reset_boss_arenas,
+                    update_boss_phase,
+                    update_boss_arenas,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 
 pub const DEATH_DESPAWN_TIME: f32 = 3.0;
+pub const PLAYER_ATTACK_DAMAGE: f32 = 25.0;
+/// A crouched swing at an unaware enemy from within this cosine of straight
+/// behind it is a takedown and kills outright.
+pub const TAKEDOWN_BEHIND_COS: f32 = 0.5;
 
 #[derive(Component, Default)]
 pub struct CombatStatus {
+    pub is_attacking: bool,
     pub is_blocking: bool,
     pub is_hit: bool,
     pub is_dead: bool,
@@ -10,11 +10,16 @@ pub use hit::*;
 pub use stamina::*;
 pub use system::*;
 
+use crate::states::AppState;
+
 pub struct CombatPlugin;
 
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<HitEvent>()
-            .add_systems(Update, (combat_system, recover_stamina));
+            .add_systems(
+                Update,
+                (combat_system, recover_stamina).run_if(in_state(AppState::Playing)),
+            );
     }
 }
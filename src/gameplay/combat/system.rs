@@ -1,22 +1,39 @@
 use bevy::prelude::*;
 
-use super::components::{CombatStatus, DEATH_DESPAWN_TIME};
+use super::components::{
+    CombatStatus, DEATH_DESPAWN_TIME, PLAYER_ATTACK_DAMAGE, TAKEDOWN_BEHIND_COS,
+};
 use super::hit::HitEvent;
 use super::stamina::{Stamina, ATTACK_STAMINA_COST, BLOCK_STAMINA_DRAIN};
-use crate::gameplay::ai::{AiState, Enemy, EnemyAi, ENEMY_ATTACK_RANGE};
+use crate::core::input::PlayerInput;
+use crate::gameplay::ai::{AiState, Enemy, EnemyAi, EnemyStats, ENEMY_ATTACK_RANGE};
+use crate::gameplay::boss::Boss;
+use crate::gameplay::difficulty::DifficultySettings;
 use crate::gameplay::health::Health;
-use crate::gameplay::player::{CurrentAnimation, GameAnimations, Player};
+use crate::gameplay::inventory::{Inventory, ItemDatabase, UsingItem};
+use crate::gameplay::player::{Crouching, CurrentAnimation, GameAnimations, Player};
 
 pub fn combat_system(
     time: Res<Time>,
-    mouse_button: Res<ButtonInput<MouseButton>>,
+    input: Res<PlayerInput>,
     animations: Option<Res<GameAnimations>>,
+    difficulty: Res<DifficultySettings>,
+    items: Res<ItemDatabase>,
     mut player_query: Query<
-        (Entity, &Transform, &mut Health, &mut CombatStatus, &mut Stamina),
+        (
+            Entity,
+            &Transform,
+            &mut Health,
+            &mut CombatStatus,
+            &mut Stamina,
+            Has<Crouching>,
+            Option<&Inventory>,
+            Has<UsingItem>,
+        ),
         (With<Player>, Without<Enemy>),
     >,
     mut enemy_query: Query<
-        (Entity, &Transform, &mut Health, &mut CombatStatus, &EnemyAi),
+        (Entity, &Transform, &mut Health, &mut CombatStatus, &EnemyAi, &EnemyStats, Has<Boss>),
         (With<Enemy>, Without<Player>),
     >,
     children: Query<&Children>,
@@ -27,13 +44,24 @@ pub fn combat_system(
         return;
     };
 
-    let Ok((player_entity, player_transform, mut player_health, mut player_combat, mut player_stamina)) =
-        player_query.get_single_mut()
+    let Ok((
+        player_entity,
+        player_transform,
+        mut player_health,
+        mut player_combat,
+        mut player_stamina,
+        player_crouching,
+        player_inventory,
+        player_using_item,
+    )) = player_query.get_single_mut()
     else {
         return;
     };
+    let attack_damage = player_inventory
+        .and_then(|inventory| inventory.weapon_damage(&items))
+        .unwrap_or(PLAYER_ATTACK_DAMAGE);
 
-    let wants_to_block = mouse_button.pressed(MouseButton::Right) && !player_combat.is_dead;
+    let wants_to_block = input.blocking && !player_combat.is_dead && !player_using_item;
     
     if wants_to_block && player_stamina.current > 0.0 {
         player_combat.is_blocking = true;
@@ -55,8 +83,22 @@ pub fn combat_system(
 
     let player_pos = player_transform.translation;
 
-    for (enemy_entity, enemy_transform, mut enemy_health, mut enemy_combat, enemy_ai) in
-        enemy_query.iter_mut()
+    let is_player_attacking = find_animation_entity(player_entity, &children, &anim_query)
+        .and_then(|anim_entity| anim_query.get(anim_entity).ok())
+        .is_some_and(|(anim_player, current_anim)| {
+            current_anim.0 == Some(animations.attack_index) && !anim_player.all_finished()
+        });
+    player_combat.is_attacking = is_player_attacking;
+
+    for (
+        enemy_entity,
+        enemy_transform,
+        mut enemy_health,
+        mut enemy_combat,
+        enemy_ai,
+        enemy_stats,
+        is_boss,
+    ) in enemy_query.iter_mut()
     {
         let enemy_pos = enemy_transform.translation;
         let distance = player_pos.distance(enemy_pos);
@@ -96,7 +138,7 @@ pub fn combat_system(
                     position: impact_pos,
                     blocked: false,
                 });
-                player_health.current -= 20.0;
+                player_health.current -= enemy_stats.attack_damage * difficulty.damage_taken;
                 info!("Player hit! Health: {}/{}", player_health.current, player_health.max);
                 player_combat.is_hit = true;
                 player_combat.hit_timer = 0.5;
@@ -130,54 +172,72 @@ pub fn combat_system(
             }
         }
 
-        if let Some(player_anim_entity) = find_animation_entity(player_entity, &children, &anim_query)
+        if is_player_attacking
+            && distance < ENEMY_ATTACK_RANGE * 1.5
+            && !enemy_combat.is_hit
+            && !enemy_combat.is_dead
         {
-            if let Ok((anim_player, current_anim)) = anim_query.get(player_anim_entity) {
-                let is_player_attacking = current_anim.0 == Some(animations.attack_index)
-                    && !anim_player.all_finished();
-
-                if is_player_attacking
-                    && distance < ENEMY_ATTACK_RANGE * 1.5
-                    && !enemy_combat.is_hit
-                    && !enemy_combat.is_dead
-                {
-                    let impact_pos = enemy_pos.lerp(player_pos, 0.3) + Vec3::Y * 0.2;
-                    hit_events.send(HitEvent {
-                        position: impact_pos,
-                        blocked: false,
-                    });
-                    enemy_health.current -= 25.0;
+            let impact_pos = enemy_pos.lerp(player_pos, 0.3) + Vec3::Y * 0.2;
+
+            // Sneaking up behind an enemy that hasn't noticed us finishes it in one blow
+            let enemy_back = enemy_transform.rotation * Vec3::NEG_Z;
+            let from_behind = enemy_back.dot((player_pos - enemy_pos).with_y(0.0).normalize_or_zero())
+                > TAKEDOWN_BEHIND_COS;
+            let takedown = player_crouching
+                && from_behind
+                && !is_boss
+                && matches!(enemy_ai.state, AiState::Idle | AiState::Patrol | AiState::Return);
+
+            if enemy_combat.is_blocking && !takedown {
+                info!("Enemy blocked attack!");
+                hit_events.send(HitEvent {
+                    position: impact_pos,
+                    blocked: true,
+                });
+                // Reuse the hit timer so a single swing is only blocked once
+                enemy_combat.is_hit = true;
+                enemy_combat.hit_timer = 0.5;
+            } else {
+                hit_events.send(HitEvent {
+                    position: impact_pos,
+                    blocked: false,
+                });
+                if takedown {
+                    enemy_health.current = 0.0;
+                    info!("Stealth takedown!");
+                } else {
+                    enemy_health.current -= attack_damage * difficulty.damage_dealt;
                     info!("Enemy hit! Health: {}/{}", enemy_health.current, enemy_health.max);
-                    enemy_combat.is_hit = true;
-                    enemy_combat.hit_timer = 0.5;
-
-                    if enemy_health.current <= 0.0 {
-                        enemy_health.current = 0.0;
-                        enemy_combat.is_dead = true;
-                        enemy_combat.death_timer = DEATH_DESPAWN_TIME;
-                        info!("Enemy died!");
-                        if let Some(anim_entity) =
-                            find_animation_entity(enemy_entity, &children, &anim_query)
+                }
+                enemy_combat.is_hit = true;
+                enemy_combat.hit_timer = 0.5;
+
+                if enemy_health.current <= 0.0 {
+                    enemy_health.current = 0.0;
+                    enemy_combat.is_dead = true;
+                    enemy_combat.death_timer = DEATH_DESPAWN_TIME;
+                    info!("Enemy died!");
+                    if let Some(anim_entity) =
+                        find_animation_entity(enemy_entity, &children, &anim_query)
+                    {
+                        if let Ok((mut anim_player, mut current_anim)) =
+                            anim_query.get_mut(anim_entity)
                         {
-                            if let Ok((mut anim_player, mut current_anim)) =
-                                anim_query.get_mut(anim_entity)
-                            {
-                                anim_player.stop_all();
-                                anim_player.play(animations.death_index);
-                                current_anim.0 = Some(animations.death_index);
-                            }
+                            anim_player.stop_all();
+                            anim_player.play(animations.death_index);
+                            current_anim.0 = Some(animations.death_index);
                         }
-                    } else {
-                        if let Some(anim_entity) =
-                            find_animation_entity(enemy_entity, &children, &anim_query)
+                    }
+                } else {
+                    if let Some(anim_entity) =
+                        find_animation_entity(enemy_entity, &children, &anim_query)
+                    {
+                        if let Ok((mut anim_player, mut current_anim)) =
+                            anim_query.get_mut(anim_entity)
                         {
-                            if let Ok((mut anim_player, mut current_anim)) =
-                                anim_query.get_mut(anim_entity)
-                            {
-                                anim_player.stop_all();
-                                anim_player.play(animations.hit_index);
-                                current_anim.0 = Some(animations.hit_index);
-                            }
+                            anim_player.stop_all();
+                            anim_player.play(animations.hit_index);
+                            current_anim.0 = Some(animations.hit_index);
                         }
                     }
                 }
@@ -186,7 +246,7 @@ pub fn combat_system(
     }
 }
 
-fn find_animation_entity(
+pub fn find_animation_entity(
     character: Entity,
     children: &Query<&Children>,
     anim_query: &Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
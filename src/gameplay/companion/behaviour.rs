@@ -0,0 +1,333 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::*;
+use crate::gameplay::ai::{AiState, Enemy, EnemyAi, EnemyStats, ENEMY_ATTACK_RANGE};
+use crate::gameplay::combat::{find_animation_entity, CombatStatus, HitEvent, DEATH_DESPAWN_TIME};
+use crate::gameplay::difficulty::DifficultySettings;
+use crate::gameplay::health::Health;
+use crate::gameplay::player::{CurrentAnimation, GameAnimations, Player};
+
+const GRAVITY: f32 = -20.0;
+
+pub fn companion_ai(
+    time: Res<Time>,
+    animations: Option<Res<GameAnimations>>,
+    player_query: Query<&Transform, (With<Player>, Without<Companion>)>,
+    enemy_query: Query<
+        (Entity, &Transform, &EnemyAi, &CombatStatus),
+        (With<Enemy>, Without<Companion>),
+    >,
+    mut companion_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut CompanionAi,
+            &mut KinematicCharacterController,
+        ),
+        With<Companion>,
+    >,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+) {
+    let Some(animations) = animations else {
+        return;
+    };
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation;
+
+    let enemy_alive = |entity: Entity| {
+        enemy_query
+            .get(entity)
+            .is_ok_and(|(_, _, _, combat_status)| !combat_status.is_dead)
+    };
+
+    for (companion_entity, mut transform, mut ai, mut controller) in companion_query.iter_mut() {
+        let position = transform.translation;
+        let mut movement = Vec3::new(0.0, GRAVITY * time.delta_secs(), 0.0);
+        let mut new_swing = false;
+
+        let desired_anim = if ai.state == CompanionState::Downed {
+            Some(animations.death_index)
+        } else {
+            ai.state_timer += time.delta_secs();
+
+            if let CompanionOrder::AttackTarget(target) = ai.order {
+                if !enemy_alive(target) {
+                    ai.order = CompanionOrder::Follow;
+                }
+            }
+            if ai.target.is_some_and(|target| !enemy_alive(target)) {
+                ai.target = None;
+            }
+
+            let anchor = match ai.order {
+                CompanionOrder::Hold(hold_position) => hold_position,
+                _ => player_pos,
+            };
+
+            ai.target = match ai.order {
+                CompanionOrder::AttackTarget(target) => Some(target),
+                // Defend the anchor: pick the closest enemy that is going after the player
+                _ => ai
+                    .target
+                    .filter(|&target| {
+                        enemy_query
+                            .get(target)
+                            .is_ok_and(|(_, enemy_transform, _, _)| {
+                                enemy_transform.translation.distance(anchor)
+                                    < COMPANION_TETHER_RANGE
+                            })
+                    })
+                    .or_else(|| {
+                        enemy_query
+                            .iter()
+                            .filter(|(_, enemy_transform, enemy_ai, combat_status)| {
+                                !combat_status.is_dead
+                                    && enemy_ai.state.is_engaged()
+                                    && enemy_transform.translation.distance(anchor)
+                                        < COMPANION_ENGAGE_RANGE
+                            })
+                            .min_by(|(_, a, _, _), (_, b, _, _)| {
+                                a.translation
+                                    .distance(position)
+                                    .total_cmp(&b.translation.distance(position))
+                            })
+                            .map(|(entity, _, _, _)| entity)
+                    }),
+            };
+
+            let target_pos = ai
+                .target
+                .and_then(|target| enemy_query.get(target).ok())
+                .map(|(_, enemy_transform, _, _)| enemy_transform.translation);
+
+            let new_state = if ai.state == CompanionState::Attack
+                && ai.state_timer < COMPANION_ATTACK_DURATION
+            {
+                CompanionState::Attack
+            } else if let Some(target_pos) = target_pos {
+                if position.distance(target_pos) < COMPANION_ATTACK_RANGE {
+                    ai.state_timer = 0.0;
+                    ai.has_struck = false;
+                    new_swing = true;
+                    CompanionState::Attack
+                } else {
+                    CompanionState::Engage
+                }
+            } else if matches!(ai.order, CompanionOrder::Hold(_)) {
+                CompanionState::Hold
+            } else {
+                CompanionState::Follow
+            };
+            if new_state != ai.state {
+                if new_state != CompanionState::Attack {
+                    ai.state_timer = 0.0;
+                }
+                ai.state = new_state;
+            }
+
+            let mut move_towards = |goal: Vec3, speed: f32, transform: &mut Transform| {
+                let dir =
+                    Vec3::new(goal.x - position.x, 0.0, goal.z - position.z).normalize_or_zero();
+                movement.x = dir.x * speed * time.delta_secs();
+                movement.z = dir.z * speed * time.delta_secs();
+                if dir.length_squared() > 0.01 {
+                    transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                }
+            };
+
+            match ai.state {
+                CompanionState::Follow => {
+                    let distance =
+                        Vec2::new(player_pos.x - position.x, player_pos.z - position.z).length();
+                    if distance > COMPANION_CATCH_UP_DISTANCE {
+                        move_towards(player_pos, COMPANION_RUN_SPEED, &mut transform);
+                        Some(animations.run_index)
+                    } else if distance > COMPANION_FOLLOW_DISTANCE {
+                        move_towards(player_pos, COMPANION_WALK_SPEED, &mut transform);
+                        Some(animations.walk_index)
+                    } else {
+                        Some(animations.idle_index)
+                    }
+                }
+                CompanionState::Hold => {
+                    let distance = Vec2::new(anchor.x - position.x, anchor.z - position.z).length();
+                    if distance > 0.1 {
+                        move_towards(anchor, COMPANION_WALK_SPEED, &mut transform);
+                        Some(animations.walk_index)
+                    } else {
+                        Some(animations.idle_index)
+                    }
+                }
+                CompanionState::Engage => {
+                    if let Some(target_pos) = target_pos {
+                        move_towards(target_pos, COMPANION_RUN_SPEED, &mut transform);
+                    }
+                    Some(animations.run_index)
+                }
+                CompanionState::Attack => {
+                    if let Some(target_pos) = target_pos {
+                        let dir =
+                            Vec3::new(target_pos.x - position.x, 0.0, target_pos.z - position.z)
+                                .normalize_or_zero();
+                        if dir.length_squared() > 0.01 {
+                            transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+                        }
+                    }
+                    Some(animations.attack_index)
+                }
+                CompanionState::Downed => Some(animations.death_index),
+            }
+        };
+
+        controller.translation = Some(movement);
+
+        if let Some(anim_entity) = find_animation_entity(companion_entity, &children, &anim_query) {
+            if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
+                if current_anim.0 != desired_anim || new_swing {
+                    anim_player.stop_all();
+                    if let Some(anim_index) = desired_anim {
+                        let active = anim_player.play(anim_index);
+                        if !matches!(ai.state, CompanionState::Attack | CompanionState::Downed) {
+                            active.repeat();
+                        }
+                    }
+                    current_anim.0 = desired_anim;
+                }
+            }
+        }
+    }
+}
+
+pub fn companion_combat(
+    time: Res<Time>,
+    animations: Option<Res<GameAnimations>>,
+    difficulty: Res<DifficultySettings>,
+    mut companion_query: Query<
+        (&Transform, &mut CompanionAi, &mut Health, &mut CombatStatus),
+        (With<Companion>, Without<Enemy>),
+    >,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Health,
+            &mut CombatStatus,
+            &EnemyAi,
+            &EnemyStats,
+        ),
+        (With<Enemy>, Without<Companion>),
+    >,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+    mut hit_events: EventWriter<HitEvent>,
+) {
+    let Some(animations) = animations else {
+        return;
+    };
+
+    for (transform, mut ai, mut health, mut combat_status) in companion_query.iter_mut() {
+        if ai.state == CompanionState::Downed {
+            continue;
+        }
+        let position = transform.translation;
+
+        if combat_status.is_hit {
+            combat_status.hit_timer -= time.delta_secs();
+            if combat_status.hit_timer <= 0.0 {
+                combat_status.is_hit = false;
+            }
+        }
+        combat_status.is_attacking = ai.state == CompanionState::Attack;
+
+        // Our swing connects once, part-way through the animation
+        if ai.state == CompanionState::Attack
+            && !ai.has_struck
+            && ai.state_timer >= COMPANION_ATTACK_HIT_TIME
+        {
+            ai.has_struck = true;
+            let target = ai
+                .target
+                .and_then(|target| enemy_query.get_mut(target).ok());
+            if let Some((enemy_entity, enemy_transform, mut enemy_health, mut enemy_combat, _, _)) =
+                target
+            {
+                let enemy_pos = enemy_transform.translation;
+                if !enemy_combat.is_dead
+                    && position.distance(enemy_pos) < COMPANION_ATTACK_RANGE * 1.5
+                {
+                    let impact_pos = enemy_pos.lerp(position, 0.3) + Vec3::Y * 0.2;
+                    hit_events.send(HitEvent {
+                        position: impact_pos,
+                        blocked: enemy_combat.is_blocking,
+                    });
+
+                    if !enemy_combat.is_blocking {
+                        enemy_health.current -= COMPANION_ATTACK_DAMAGE;
+                        info!(
+                            "Companion hit enemy! Health: {}/{}",
+                            enemy_health.current, enemy_health.max
+                        );
+
+                        let reaction = if enemy_health.current <= 0.0 {
+                            enemy_health.current = 0.0;
+                            enemy_combat.is_dead = true;
+                            enemy_combat.death_timer = DEATH_DESPAWN_TIME;
+                            animations.death_index
+                        } else {
+                            animations.hit_index
+                        };
+                        if let Some(anim_entity) =
+                            find_animation_entity(enemy_entity, &children, &anim_query)
+                        {
+                            if let Ok((mut anim_player, mut current_anim)) =
+                                anim_query.get_mut(anim_entity)
+                            {
+                                anim_player.stop_all();
+                                anim_player.play(reaction);
+                                current_anim.0 = Some(reaction);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Enemy swings catch the companion too when it stands in range
+        if combat_status.is_hit {
+            continue;
+        }
+        let incoming =
+            enemy_query
+                .iter()
+                .find(|(_, enemy_transform, _, enemy_combat, enemy_ai, _)| {
+                    !enemy_combat.is_dead
+                        && enemy_ai.state == AiState::Attack
+                        && enemy_transform.translation.distance(position) < ENEMY_ATTACK_RANGE
+                });
+        let Some((_, enemy_transform, _, _, _, enemy_stats)) = incoming else {
+            continue;
+        };
+
+        hit_events.send(HitEvent {
+            position: position.lerp(enemy_transform.translation, 0.3) + Vec3::Y * 0.2,
+            blocked: false,
+        });
+        health.current -= enemy_stats.attack_damage * difficulty.damage_taken;
+        combat_status.is_hit = true;
+        combat_status.hit_timer = 0.5;
+
+        if health.current <= 0.0 {
+            health.current = 0.0;
+            combat_status.is_dead = true;
+            combat_status.is_attacking = false;
+            ai.state = CompanionState::Downed;
+            ai.target = None;
+            ai.revive_progress = 0.0;
+            info!("Companion is down!");
+        }
+    }
+}
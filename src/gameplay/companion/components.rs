@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+pub const COMPANION_WALK_SPEED: f32 = 0.6;
+pub const COMPANION_RUN_SPEED: f32 = 1.6;
+/// How far behind the player the companion settles when following.
+pub const COMPANION_FOLLOW_DISTANCE: f32 = 0.5;
+/// Beyond this the companion runs to catch up instead of walking.
+pub const COMPANION_CATCH_UP_DISTANCE: f32 = 1.2;
+/// Enemies this close to the player are fair game while following.
+pub const COMPANION_ENGAGE_RANGE: f32 = 2.0;
+/// Stop chasing a target once the player is this far away.
+pub const COMPANION_TETHER_RANGE: f32 = 3.5;
+pub const COMPANION_ATTACK_RANGE: f32 = 0.4;
+pub const COMPANION_ATTACK_DAMAGE: f32 = 15.0;
+pub const COMPANION_ATTACK_DURATION: f32 = 1.2;
+/// Point in the swing where the blade connects.
+pub const COMPANION_ATTACK_HIT_TIME: f32 = 0.5;
+pub const COMPANION_REVIVE_RANGE: f32 = 0.5;
+pub const COMPANION_REVIVE_TIME: f32 = 2.0;
+pub const COMPANION_REVIVE_HEALTH_FRACTION: f32 = 0.5;
+/// How far ahead of the camera an "attack target" order looks for an enemy.
+pub const COMPANION_COMMAND_RANGE: f32 = 3.0;
+
+#[derive(Component)]
+#[require(CompanionAi)]
+pub struct Companion;
+
+/// Standing order given by the player.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum CompanionOrder {
+    /// Stay near the player and defend them.
+    #[default]
+    Follow,
+    /// Stay put at the given position, only fighting enemies that come close.
+    Hold(Vec3),
+    /// Go after a specific enemy, then fall back to following.
+    AttackTarget(Entity),
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum CompanionState {
+    #[default]
+    Follow,
+    Hold,
+    Engage,
+    Attack,
+    Downed,
+}
+
+#[derive(Component, Default)]
+pub struct CompanionAi {
+    pub order: CompanionOrder,
+    pub state: CompanionState,
+    pub target: Option<Entity>,
+    pub state_timer: f32,
+    pub has_struck: bool,
+    pub revive_progress: f32,
+}
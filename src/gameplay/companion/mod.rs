@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+mod behaviour;
+mod components;
+mod orders;
+
+pub use behaviour::*;
+pub use components::*;
+pub use orders::*;
+
+use crate::states::AppState;
+
+pub struct CompanionPlugin;
+
+impl Plugin for CompanionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                companion_orders,
+                revive_companion,
+                companion_ai,
+                companion_combat,
+            )
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use super::components::*;
+use crate::core::camera::PlayerYaw;
+use crate::core::input::PlayerInput;
+use crate::gameplay::ai::Enemy;
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::health::Health;
+use crate::gameplay::player::Player;
+
+/// Only enemies within this angle of the camera's forward can be picked as a target.
+const TARGET_CONE_COS: f32 = 0.8;
+
+pub fn companion_orders(
+    input: Res<PlayerInput>,
+    player_yaw: Res<PlayerYaw>,
+    player_query: Query<&Transform, (With<Player>, Without<Companion>)>,
+    enemy_query: Query<(Entity, &Transform, &CombatStatus), (With<Enemy>, Without<Companion>)>,
+    mut companion_query: Query<(&Transform, &mut CompanionAi), With<Companion>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    for (transform, mut ai) in companion_query.iter_mut() {
+        if input.companion_follow {
+            info!("Companion: follow");
+            ai.order = CompanionOrder::Follow;
+        } else if input.companion_hold {
+            info!("Companion: hold position");
+            ai.order = CompanionOrder::Hold(transform.translation);
+        } else if input.companion_attack {
+            // Pick the closest living enemy roughly where the camera is looking
+            let forward = Vec3::new(-player_yaw.0.sin(), 0.0, -player_yaw.0.cos());
+            let target = enemy_query
+                .iter()
+                .filter_map(|(entity, enemy_transform, combat_status)| {
+                    let offset = enemy_transform.translation - player_transform.translation;
+                    let flat = Vec3::new(offset.x, 0.0, offset.z);
+                    let distance = flat.length();
+                    (!combat_status.is_dead
+                        && distance < COMPANION_COMMAND_RANGE
+                        && flat.normalize_or_zero().dot(forward) > TARGET_CONE_COS)
+                        .then_some((entity, distance))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(entity, _)| entity);
+
+            if let Some(target) = target {
+                info!("Companion: attack target");
+                ai.order = CompanionOrder::AttackTarget(target);
+                ai.target = Some(target);
+            }
+        }
+    }
+}
+
+/// Hold Interact next to a downed companion to get them back on their feet.
+pub fn revive_companion(
+    time: Res<Time>,
+    input: Res<PlayerInput>,
+    player_query: Query<(&Transform, &CombatStatus), (With<Player>, Without<Companion>)>,
+    mut companion_query: Query<
+        (&Transform, &mut CompanionAi, &mut Health, &mut CombatStatus),
+        With<Companion>,
+    >,
+) {
+    let Ok((player_transform, player_combat)) = player_query.get_single() else {
+        return;
+    };
+
+    for (transform, mut ai, mut health, mut combat_status) in companion_query.iter_mut() {
+        if ai.state != CompanionState::Downed {
+            continue;
+        }
+
+        let in_reach =
+            player_transform.translation.distance(transform.translation) < COMPANION_REVIVE_RANGE;
+        if !(in_reach && input.interacting && !player_combat.is_dead) {
+            ai.revive_progress = 0.0;
+            continue;
+        }
+
+        ai.revive_progress += time.delta_secs();
+        if ai.revive_progress >= COMPANION_REVIVE_TIME {
+            info!("Companion revived!");
+            health.current = health.max * COMPANION_REVIVE_HEALTH_FRACTION;
+            combat_status.is_dead = false;
+            combat_status.is_hit = false;
+            ai.state = CompanionState::Follow;
+            ai.order = CompanionOrder::Follow;
+            ai.state_timer = 0.0;
+            ai.revive_progress = 0.0;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+
+use crate::gameplay::ai::{AiDirector, Enemy};
+use crate::gameplay::health::Health;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Any hand-tuned combination of multipliers.
+    Custom,
+}
+
+impl Difficulty {
+    pub const PRESETS: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+/// Multipliers applied on top of the base combat and AI tuning.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct DifficultySettings {
+    pub preset: Difficulty,
+    /// Damage the player deals to enemies.
+    pub damage_dealt: f32,
+    /// Damage enemies deal to the player.
+    pub damage_taken: f32,
+    pub enemy_health: f32,
+    /// Scales how long enemies take to react to a swing; higher is easier.
+    pub reaction_time: f32,
+    pub max_attackers: usize,
+}
+
+impl DifficultySettings {
+    pub fn preset(preset: Difficulty) -> Self {
+        match preset {
+            Difficulty::Easy => Self {
+                preset,
+                damage_dealt: 1.5,
+                damage_taken: 0.5,
+                enemy_health: 0.75,
+                reaction_time: 2.0,
+                max_attackers: 1,
+            },
+            Difficulty::Normal | Difficulty::Custom => Self {
+                preset,
+                damage_dealt: 1.0,
+                damage_taken: 1.0,
+                enemy_health: 1.0,
+                reaction_time: 1.0,
+                max_attackers: 2,
+            },
+            Difficulty::Hard => Self {
+                preset,
+                damage_dealt: 0.8,
+                damage_taken: 1.5,
+                enemy_health: 1.5,
+                reaction_time: 0.5,
+                max_attackers: 3,
+            },
+        }
+    }
+}
+
+impl Default for DifficultySettings {
+    fn default() -> Self {
+        Self::preset(Difficulty::Normal)
+    }
+}
+
+/// Reads `--difficulty <easy|normal|hard>` from the command line.
+pub fn difficulty_from_args() -> Option<Difficulty> {
+    let args: Vec<String> = std::env::args().collect();
+    let position = args.iter().position(|arg| arg == "--difficulty")?;
+    let name = args.get(position + 1)?;
+    let difficulty = Difficulty::from_name(name);
+    if difficulty.is_none() {
+        warn!("Unknown difficulty '{name}', using Normal");
+    }
+    difficulty
+}
+
+/// An enemy's authored max health, before the difficulty multiplier.
+#[derive(Component)]
+pub struct BaseHealth(pub f32);
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        let difficulty = difficulty_from_args().unwrap_or_default();
+        info!("Difficulty: {:?}", difficulty);
+        app.insert_resource(DifficultySettings::preset(difficulty))
+            .add_systems(
+                Update,
+                (
+                    scale_new_enemy_health,
+                    apply_difficulty.run_if(resource_changed::<DifficultySettings>),
+                ),
+            );
+    }
+}
+
+fn scale_new_enemy_health(
+    mut commands: Commands,
+    settings: Res<DifficultySettings>,
+    mut enemy_query: Query<(Entity, &mut Health), (With<Enemy>, Without<BaseHealth>)>,
+) {
+    for (entity, mut health) in enemy_query.iter_mut() {
+        let base = health.max;
+        health.max = base * settings.enemy_health;
+        health.current = health.max;
+        commands.entity(entity).insert(BaseHealth(base));
+    }
+}
+
+fn apply_difficulty(
+    settings: Res<DifficultySettings>,
+    mut director: ResMut<AiDirector>,
+    mut enemy_query: Query<(&mut Health, &BaseHealth), With<Enemy>>,
+) {
+    director.max_attackers = settings.max_attackers;
+
+    // Keep each enemy's health fraction when the multiplier changes mid-fight
+    for (mut health, base) in enemy_query.iter_mut() {
+        let fraction = health.current / health.max;
+        health.max = base.0 * settings.enemy_health;
+        health.current = health.max * fraction;
+    }
+}
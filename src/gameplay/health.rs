@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::gameplay::ai::Enemy;
 use crate::gameplay::combat::CombatStatus;
+use crate::states::AppState;
 use crate::visual::health_bar::{EnemyHealthBar, EnemyHealthBarFill};
 
 #[derive(Component)]
@@ -23,7 +24,10 @@ pub struct HealthPlugin;
 
 impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, despawn_dead_enemies);
+        app.add_systems(
+            Update,
+            despawn_dead_enemies.run_if(in_state(AppState::Playing)),
+        );
     }
 }
 
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+pub const INTERACTION_RANGE: f32 = 0.4;
+/// How far off the player's facing an interactable can be and still be picked, in radians.
+pub const INTERACTION_FACING_ANGLE: f32 = 1.0;
+
+/// Something the player can use with the Interact action when close to it and facing it.
+#[derive(Component, Clone)]
+pub struct Interactable {
+    /// Shown in the HUD prompt, e.g. "Open door".
+    pub prompt: String,
+    pub range: f32,
+    /// Maximum angle between the player's facing and the direction to the
+    /// interactable; `None` allows any facing.
+    pub facing_angle: Option<f32>,
+    /// Point in the entity's local space that range and facing are measured to.
+    pub offset: Vec3,
+    /// Disabled interactables can't be focused or used.
+    pub enabled: bool,
+}
+
+impl Interactable {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            range: INTERACTION_RANGE,
+            facing_angle: Some(INTERACTION_FACING_ANGLE),
+            offset: Vec3::ZERO,
+            enabled: true,
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = range;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn any_facing(mut self) -> Self {
+        self.facing_angle = None;
+        self
+    }
+}
+
+/// The interactable the player would use if they pressed Interact now.
+#[derive(Resource, Default)]
+pub struct InteractionFocus(pub Option<Entity>);
+
+/// Sent when the player uses an interactable; game code reacts to the ones it owns.
+#[derive(Event)]
+pub struct Interacted {
+    pub target: Entity,
+    pub interactor: Entity,
+}
+
+pub const DOOR_OPEN_ANGLE: f32 = 1.6;
+/// Fraction of the swing covered per second.
+pub const DOOR_SWING_SPEED: f32 = 1.5;
+pub const LEVER_THROW_ANGLE: f32 = 0.6;
+
+/// Hinged door. The entity's transform is the hinge; the panel (mesh and
+/// collider) is a child, so the collider swings with it.
+#[derive(Component)]
+pub struct Door {
+    pub open: bool,
+    /// Swing when fully open, in radians about the hinge (positive is anticlockwise from above).
+    pub open_angle: f32,
+    pub closed_rotation: Quat,
+    /// 0.0 closed to 1.0 open.
+    pub openness: f32,
+    /// Item id the interactor must carry to open this door by hand. Levers ignore it.
+    pub key: Option<String>,
+}
+
+impl Door {
+    pub fn new(closed_rotation: Quat) -> Self {
+        Self {
+            open: false,
+            open_angle: DOOR_OPEN_ANGLE,
+            closed_rotation,
+            openness: 0.0,
+            key: None,
+        }
+    }
+
+    pub fn locked_with(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        if self.open {
+            "Close door"
+        } else {
+            "Open door"
+        }
+    }
+}
+
+/// Toggles every door in `targets` each time it's pulled.
+#[derive(Component)]
+pub struct Lever {
+    pub targets: Vec<Entity>,
+    pub pulled: bool,
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use super::components::{
+    Door, Interactable, Interacted, Lever, DOOR_SWING_SPEED, LEVER_THROW_ANGLE,
+};
+use crate::gameplay::inventory::Inventory;
+
+fn toggle_door(door: &mut Door, interactable: Option<Mut<Interactable>>) {
+    door.open = !door.open;
+    if let Some(mut interactable) = interactable {
+        interactable.prompt = door.prompt().to_string();
+    }
+}
+
+pub fn use_doors(
+    mut interacted_events: EventReader<Interacted>,
+    mut door_query: Query<(&mut Door, Option<&mut Interactable>)>,
+    inventory_query: Query<&Inventory>,
+) {
+    for event in interacted_events.read() {
+        let Ok((mut door, interactable)) = door_query.get_mut(event.target) else {
+            continue;
+        };
+        if let Some(key) = &door.key {
+            let has_key = inventory_query
+                .get(event.interactor)
+                .is_ok_and(|inventory| inventory.has(key));
+            if !has_key {
+                info!("The door is locked");
+                continue;
+            }
+        }
+        toggle_door(&mut door, interactable);
+    }
+}
+
+pub fn pull_levers(
+    mut interacted_events: EventReader<Interacted>,
+    mut lever_query: Query<(&mut Lever, &mut Transform)>,
+    mut door_query: Query<(&mut Door, Option<&mut Interactable>)>,
+) {
+    for event in interacted_events.read() {
+        let Ok((mut lever, mut transform)) = lever_query.get_mut(event.target) else {
+            continue;
+        };
+        lever.pulled = !lever.pulled;
+        let throw = if lever.pulled { 2.0 } else { -2.0 } * LEVER_THROW_ANGLE;
+        transform.rotate_local_x(throw);
+
+        for &target in &lever.targets {
+            if let Ok((mut door, interactable)) = door_query.get_mut(target) {
+                toggle_door(&mut door, interactable);
+            }
+        }
+    }
+}
+
+/// Eases each door towards its open or closed angle. The panel collider is a
+/// child of the hinge, so Rapier moves it with the transform.
+pub fn swing_doors(time: Res<Time>, mut door_query: Query<(&mut Door, &mut Transform)>) {
+    for (mut door, mut transform) in door_query.iter_mut() {
+        let target = if door.open { 1.0 } else { 0.0 };
+        if door.openness == target {
+            continue;
+        }
+        let step = DOOR_SWING_SPEED * time.delta_secs();
+        door.openness = if door.openness < target {
+            (door.openness + step).min(target)
+        } else {
+            (door.openness - step).max(target)
+        };
+        transform.rotation =
+            door.closed_rotation * Quat::from_rotation_y(door.open_angle * door.openness);
+    }
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use super::components::{Interactable, Interacted, InteractionFocus};
+use crate::core::input::PlayerInput;
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::inventory::UsingItem;
+use crate::gameplay::player::{Dodging, Player, Traversal};
+
+/// Focus is only tracked while playing, so drop it when a menu opens rather than
+/// leaving its prompt up behind the menu.
+pub fn clear_interaction_focus(mut focus: ResMut<InteractionFocus>) {
+    focus.0 = None;
+}
+
+/// Picks the nearest enabled interactable that's in range and in front of the player.
+pub fn update_interaction_focus(
+    mut focus: ResMut<InteractionFocus>,
+    player_query: Query<(&Transform, &CombatStatus), With<Player>>,
+    interactable_query: Query<(Entity, &GlobalTransform, &Interactable)>,
+) {
+    let Ok((player_transform, combat_status)) = player_query.get_single() else {
+        return;
+    };
+    if combat_status.is_dead {
+        focus.0 = None;
+        return;
+    }
+
+    let player_pos = player_transform.translation;
+    let facing = (player_transform.rotation * Vec3::Z)
+        .with_y(0.0)
+        .normalize_or_zero();
+
+    let nearest = interactable_query
+        .iter()
+        .filter(|(_, _, interactable)| interactable.enabled)
+        .filter_map(|(entity, transform, interactable)| {
+            let point = transform.transform_point(interactable.offset);
+            let distance = player_pos.distance(point);
+            if distance > interactable.range {
+                return None;
+            }
+            if let Some(angle) = interactable.facing_angle {
+                let direction = (point - player_pos).with_y(0.0).normalize_or_zero();
+                if facing.dot(direction) < angle.cos() {
+                    return None;
+                }
+            }
+            Some((entity, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity);
+
+    if focus.0 != nearest {
+        focus.0 = nearest;
+    }
+}
+
+pub fn interact(
+    input: Res<PlayerInput>,
+    focus: Res<InteractionFocus>,
+    player_query: Query<
+        Entity,
+        (
+            With<Player>,
+            Without<Dodging>,
+            Without<Traversal>,
+            Without<UsingItem>,
+        ),
+    >,
+    mut interacted_events: EventWriter<Interacted>,
+) {
+    if !input.interact {
+        return;
+    }
+    let (Some(target), Ok(player)) = (focus.0, player_query.get_single()) else {
+        return;
+    };
+    interacted_events.send(Interacted {
+        target,
+        interactor: player,
+    });
+}
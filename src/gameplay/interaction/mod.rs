@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+mod components;
+mod doors;
+mod focus;
+
+pub use components::*;
+pub use doors::*;
+pub use focus::*;
+
+use crate::states::AppState;
+
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InteractionFocus>()
+            .add_event::<Interacted>()
+            .add_systems(OnExit(AppState::Playing), clear_interaction_focus)
+            .add_systems(
+                Update,
+                (
+                    update_interaction_focus,
+                    interact,
+                    (use_doors, pull_levers),
+                    swing_doors,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,338 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::items::{ItemDatabase, ItemDef, ItemKind};
+
+pub const INVENTORY_CAPACITY: usize = 12;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u32,
+}
+
+/// Items carried by an entity, as stacks in slot order.
+#[derive(Component, Clone, Debug)]
+pub struct Inventory {
+    pub stacks: Vec<ItemStack>,
+    /// Most stacks this inventory can hold.
+    pub capacity: usize,
+    pub equipped_weapon: Option<String>,
+    /// Charges left in each flask carried, by item id.
+    pub charges: HashMap<String, u32>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            stacks: Vec::new(),
+            capacity: INVENTORY_CAPACITY,
+            equipped_weapon: None,
+            charges: HashMap::default(),
+        }
+    }
+}
+
+impl Inventory {
+    pub fn count(&self, item: &str) -> u32 {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    pub fn has(&self, item: &str) -> bool {
+        self.count(item) > 0
+    }
+
+    /// Adds up to `count` of `item`, topping up existing stacks before starting
+    /// new ones. Returns how many didn't fit.
+    pub fn add(&mut self, item: &ItemDef, count: u32) -> u32 {
+        let carried = self.has(&item.id);
+        let mut remaining = count;
+        for stack in self.stacks.iter_mut().filter(|stack| stack.item == item.id) {
+            let added = remaining.min(item.max_stack.saturating_sub(stack.count));
+            stack.count += added;
+            remaining -= added;
+        }
+        while remaining > 0 && self.stacks.len() < self.capacity {
+            let added = remaining.min(item.max_stack.max(1));
+            self.stacks.push(ItemStack {
+                item: item.id.clone(),
+                count: added,
+            });
+            remaining -= added;
+        }
+        if let ItemKind::Flask { charges, .. } = item.kind {
+            // A newly found flask comes full, as long as it fitted
+            if !carried && remaining < count {
+                self.charges.insert(item.id.clone(), charges);
+            }
+        }
+        remaining
+    }
+
+    /// Removes `count` of `item`, emptying the last stacks first. Does nothing and
+    /// returns false if there aren't that many.
+    pub fn remove(&mut self, item: &str, count: u32) -> bool {
+        if self.count(item) < count {
+            return false;
+        }
+        let mut remaining = count;
+        for stack in self
+            .stacks
+            .iter_mut()
+            .rev()
+            .filter(|stack| stack.item == item)
+        {
+            let removed = remaining.min(stack.count);
+            stack.count -= removed;
+            remaining -= removed;
+        }
+        self.stacks.retain(|stack| stack.count > 0);
+        if !self.has(item) {
+            if self.equipped_weapon.as_deref() == Some(item) {
+                self.equipped_weapon = None;
+            }
+            self.charges.remove(item);
+        }
+        true
+    }
+
+    pub fn charges(&self, item: &str) -> u32 {
+        self.charges.get(item).copied().unwrap_or(0)
+    }
+
+    /// Whether `item` can be used right now: carried, and for flasks not empty.
+    pub fn can_use(&self, item: &ItemDef) -> bool {
+        match item.kind {
+            ItemKind::Flask { .. } => self.has(&item.id) && self.charges(&item.id) > 0,
+            _ => self.has(&item.id),
+        }
+    }
+
+    /// Uses up one of `item`: a charge for flasks, the item itself otherwise.
+    pub fn consume(&mut self, item: &ItemDef) -> bool {
+        match item.kind {
+            ItemKind::Flask { .. } => match self.charges.get_mut(&item.id) {
+                Some(charges) if *charges > 0 => {
+                    *charges -= 1;
+                    true
+                }
+                _ => false,
+            },
+            _ => self.remove(&item.id, 1),
+        }
+    }
+
+    /// Whether any flask is short of charges. Checked before
+    /// [`refill_flasks`](Self::refill_flasks) so full inventories aren't marked changed.
+    pub fn needs_refill(&self, items: &ItemDatabase) -> bool {
+        self.charges.iter().any(|(id, &left)| {
+            items.get(id).is_some_and(
+                |item| matches!(item.kind, ItemKind::Flask { charges, .. } if left < charges),
+            )
+        })
+    }
+
+    pub fn refill_flasks(&mut self, items: &ItemDatabase) {
+        for (id, left) in self.charges.iter_mut() {
+            if let Some(ItemKind::Flask { charges, .. }) = items.get(id).map(|item| &item.kind) {
+                *left = *charges;
+            }
+        }
+    }
+
+    /// The item the Heal action drinks: the first flask with charges left,
+    /// otherwise the first healing consumable.
+    pub fn quick_heal<'a>(&self, items: &'a ItemDatabase) -> Option<&'a ItemDef> {
+        let carried = || {
+            self.stacks
+                .iter()
+                .filter_map(|stack| items.get(&stack.item))
+                .filter(|item| self.can_use(item))
+        };
+        carried()
+            .find(|item| matches!(item.kind, ItemKind::Flask { .. }))
+            .or_else(|| carried().find(|item| matches!(item.kind, ItemKind::Consumable { .. })))
+    }
+
+    /// Attack damage of the equipped weapon, if any.
+    pub fn weapon_damage(&self, items: &ItemDatabase) -> Option<f32> {
+        let weapon = items.get(self.equipped_weapon.as_deref()?)?;
+        match weapon.kind {
+            ItemKind::Weapon { damage } => Some(damage),
+            _ => None,
+        }
+    }
+}
+
+/// Item lying in the world; interacting with it moves it into the interactor's inventory.
+#[derive(Component)]
+pub struct Pickup {
+    pub item: String,
+    pub count: u32,
+}
+
+/// Sent whenever an entity's [`Inventory`] changes.
+#[derive(Event)]
+pub struct InventoryChanged {
+    pub owner: Entity,
+}
+
+/// Request for `owner` to use (or, for weapons, equip) one `item`.
+#[derive(Event)]
+pub struct UseItem {
+    pub owner: Entity,
+    pub item: String,
+}
+
+/// Sent once an item has actually been used; effects hang off this.
+#[derive(Event)]
+pub struct ItemUsed {
+    pub owner: Entity,
+    pub item: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEMS: &str = "\
+        [potion]\n\
+        kind = consumable\n\
+        heal = 20\n\
+        max_stack = 3\n\
+        [flask]\n\
+        kind = flask\n\
+        heal = 40\n\
+        charges = 2\n\
+        max_stack = 1\n\
+        [sword]\n\
+        kind = weapon\n\
+        damage = 30\n\
+        max_stack = 1\n";
+
+    fn item<'a>(items: &'a ItemDatabase, id: &str) -> &'a ItemDef {
+        items.get(id).unwrap()
+    }
+
+    #[test]
+    fn add_tops_up_stacks_before_starting_new_ones() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory::default();
+        assert_eq!(inventory.add(item(&items, "potion"), 2), 0);
+        assert_eq!(inventory.add(item(&items, "potion"), 2), 0);
+        assert_eq!(
+            inventory.stacks,
+            [
+                ItemStack {
+                    item: "potion".to_string(),
+                    count: 3
+                },
+                ItemStack {
+                    item: "potion".to_string(),
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn add_returns_what_didnt_fit() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory {
+            capacity: 2,
+            ..default()
+        };
+        assert_eq!(inventory.add(item(&items, "potion"), 8), 2);
+        assert_eq!(inventory.count("potion"), 6);
+    }
+
+    #[test]
+    fn new_flasks_come_full() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory::default();
+        inventory.add(item(&items, "flask"), 1);
+        assert_eq!(inventory.charges("flask"), 2);
+    }
+
+    #[test]
+    fn a_flask_that_doesnt_fit_gets_no_charges() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory {
+            capacity: 1,
+            ..default()
+        };
+        inventory.add(item(&items, "sword"), 1);
+        assert_eq!(inventory.add(item(&items, "flask"), 1), 1);
+        assert!(inventory.charges.is_empty());
+        assert!(!inventory.needs_refill(&items));
+    }
+
+    #[test]
+    fn remove_fails_without_enough_and_empties_the_last_stacks_first() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory::default();
+        inventory.add(item(&items, "potion"), 4);
+        assert!(!inventory.remove("potion", 5));
+        assert_eq!(inventory.count("potion"), 4);
+
+        assert!(inventory.remove("potion", 2));
+        assert_eq!(
+            inventory.stacks,
+            [ItemStack {
+                item: "potion".to_string(),
+                count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn removing_the_last_one_unequips_and_drops_charges() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory::default();
+        inventory.add(item(&items, "sword"), 1);
+        inventory.add(item(&items, "flask"), 1);
+        inventory.equipped_weapon = Some("sword".to_string());
+
+        assert!(inventory.remove("sword", 1));
+        assert!(inventory.remove("flask", 1));
+        assert_eq!(inventory.equipped_weapon, None);
+        assert!(inventory.charges.is_empty());
+    }
+
+    #[test]
+    fn consume_spends_flask_charges_and_other_items_themselves() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory::default();
+        inventory.add(item(&items, "flask"), 1);
+        inventory.add(item(&items, "potion"), 1);
+
+        assert!(inventory.consume(item(&items, "flask")));
+        assert!(inventory.consume(item(&items, "flask")));
+        assert!(!inventory.consume(item(&items, "flask")));
+        assert!(inventory.has("flask"));
+        assert!(!inventory.can_use(item(&items, "flask")));
+
+        assert!(inventory.consume(item(&items, "potion")));
+        assert!(!inventory.has("potion"));
+        assert!(!inventory.consume(item(&items, "potion")));
+    }
+
+    #[test]
+    fn quick_heal_prefers_a_flask_then_falls_back_to_potions() {
+        let items = ItemDatabase::parse(ITEMS);
+        let mut inventory = Inventory::default();
+        assert!(inventory.quick_heal(&items).is_none());
+
+        inventory.add(item(&items, "sword"), 1);
+        inventory.add(item(&items, "potion"), 1);
+        inventory.add(item(&items, "flask"), 1);
+        assert_eq!(inventory.quick_heal(&items).unwrap().id, "flask");
+
+        inventory.charges.insert("flask".to_string(), 0);
+        assert_eq!(inventory.quick_heal(&items).unwrap().id, "potion");
+    }
+}
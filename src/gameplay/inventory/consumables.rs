@@ -0,0 +1,185 @@
+use bevy::prelude::*;
+
+use super::components::{Inventory, ItemUsed, UseItem};
+use super::items::{ItemDatabase, ItemKind};
+use crate::core::input::PlayerInput;
+use crate::gameplay::combat::{find_animation_entity, CombatStatus};
+use crate::gameplay::health::Health;
+use crate::gameplay::player::{CurrentAnimation, GameAnimations, Player};
+use crate::gameplay::respawn::{CheckpointReached, PlayerRespawned};
+
+/// Seconds spent drinking before a healing item takes effect.
+pub const ITEM_USE_TIME: f32 = 1.2;
+pub const USE_ITEM_ANIMATION: &str = "Use_Item";
+
+/// Present while drinking a healing item. The owner is rooted; taking a hit
+/// cancels the drink before the item is used up.
+#[derive(Component)]
+pub struct UsingItem {
+    pub item: String,
+    pub timer: f32,
+}
+
+/// Health still to be restored, spread over `time_left` seconds.
+#[derive(Component)]
+pub struct HealOverTime {
+    pub amount: f32,
+    pub time_left: f32,
+}
+
+/// The Heal action drinks from the player's flask, or a potion if every flask is empty.
+pub fn quick_heal(
+    input: Res<PlayerInput>,
+    items: Res<ItemDatabase>,
+    player_query: Query<(Entity, &Inventory), With<Player>>,
+    mut use_events: EventWriter<UseItem>,
+) {
+    if !input.heal {
+        return;
+    }
+    let Ok((player, inventory)) = player_query.get_single() else {
+        return;
+    };
+    match inventory.quick_heal(&items) {
+        Some(item) => {
+            use_events.send(UseItem {
+                owner: player,
+                item: item.id.clone(),
+            });
+        }
+        None => info!("Nothing to heal with"),
+    }
+}
+
+/// Plays out a drink started by `use_items`, using the item up when it finishes.
+pub fn update_item_use(
+    mut commands: Commands,
+    time: Res<Time>,
+    items: Res<ItemDatabase>,
+    animations: Option<Res<GameAnimations>>,
+    mut user_query: Query<(Entity, &mut UsingItem, &mut Inventory, &CombatStatus)>,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+    mut used_events: EventWriter<ItemUsed>,
+) {
+    for (entity, mut using, mut inventory, combat_status) in user_query.iter_mut() {
+        if combat_status.is_dead || combat_status.is_hit {
+            info!("Drinking {} interrupted", using.item);
+            commands.entity(entity).remove::<UsingItem>();
+            continue;
+        }
+
+        let use_index = animations
+            .as_ref()
+            .and_then(|animations| animations.clips.get(USE_ITEM_ANIMATION).copied());
+        if let Some(use_index) = use_index {
+            if let Some(anim_entity) = find_animation_entity(entity, &children, &anim_query) {
+                if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
+                    if current_anim.0 != Some(use_index) {
+                        anim_player.stop_all();
+                        anim_player.play(use_index);
+                        current_anim.0 = Some(use_index);
+                    }
+                }
+            }
+        }
+
+        using.timer -= time.delta_secs();
+        if using.timer > 0.0 {
+            continue;
+        }
+        commands.entity(entity).remove::<UsingItem>();
+        let Some(item) = items.get(&using.item) else {
+            continue;
+        };
+        if inventory.consume(item) {
+            info!("Used {}", item.name);
+            used_events.send(ItemUsed {
+                owner: entity,
+                item: item.id.clone(),
+            });
+        }
+    }
+}
+
+/// Starts (or tops up) healing over time for each healing item used.
+pub fn apply_consumables(
+    mut commands: Commands,
+    items: Res<ItemDatabase>,
+    mut used_events: EventReader<ItemUsed>,
+    mut health_query: Query<Option<&mut HealOverTime>, With<Health>>,
+) {
+    for event in used_events.read() {
+        let (heal, heal_time) = match items.get(&event.item).map(|item| &item.kind) {
+            Some(ItemKind::Consumable { heal, heal_time })
+            | Some(ItemKind::Flask {
+                heal, heal_time, ..
+            }) => (*heal, *heal_time),
+            _ => continue,
+        };
+        match health_query.get_mut(event.owner) {
+            Ok(Some(mut healing)) => {
+                healing.amount += heal;
+                healing.time_left = healing.time_left.max(heal_time);
+            }
+            Ok(None) => {
+                commands.entity(event.owner).insert(HealOverTime {
+                    amount: heal,
+                    time_left: heal_time,
+                });
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+pub fn regenerate_health(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut HealOverTime,
+        &mut Health,
+        Option<&CombatStatus>,
+    )>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut healing, mut health, combat_status) in query.iter_mut() {
+        if combat_status.is_some_and(|combat_status| combat_status.is_dead) {
+            commands.entity(entity).remove::<HealOverTime>();
+            continue;
+        }
+
+        let step = if healing.time_left <= delta {
+            healing.amount
+        } else {
+            healing.amount * delta / healing.time_left
+        };
+        health.current = (health.current + step).min(health.max);
+        healing.amount -= step;
+        healing.time_left -= delta;
+        if healing.time_left <= 0.0 {
+            commands.entity(entity).remove::<HealOverTime>();
+        }
+    }
+}
+
+/// Stepping onto a checkpoint or respawning fills every flask the player carries.
+pub fn refill_flasks(
+    items: Res<ItemDatabase>,
+    mut reached_events: EventReader<CheckpointReached>,
+    mut respawned_events: EventReader<PlayerRespawned>,
+    mut inventory_query: Query<&mut Inventory, With<Player>>,
+) {
+    let reached = reached_events.read().count() > 0;
+    let respawned = respawned_events.read().count() > 0;
+    if !reached && !respawned {
+        return;
+    }
+    for mut inventory in inventory_query.iter_mut() {
+        if inventory.needs_refill(&items) {
+            inventory.refill_flasks(&items);
+            info!("Flasks refilled");
+        }
+    }
+}
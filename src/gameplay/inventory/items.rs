@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+pub const ITEMS_PATH: &str = "assets/data/items.cfg";
+/// Seconds a healing item takes to restore its full amount when `heal_time` isn't given.
+pub const DEFAULT_HEAL_TIME: f32 = 2.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemKind {
+    /// Used up one at a time.
+    Consumable {
+        /// Health restored on use, spread over `heal_time` seconds.
+        heal: f32,
+        heal_time: f32,
+    },
+    /// Never used up: drunk from `charges` times, then refilled at checkpoints.
+    Flask {
+        heal: f32,
+        heal_time: f32,
+        charges: u32,
+    },
+    /// Equipped rather than used up; replaces the player's base attack damage.
+    Weapon { damage: f32 },
+    /// Opens doors locked with its id. Can't be used directly.
+    Key,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemDef {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub kind: ItemKind,
+    /// Most of this item one inventory slot can hold.
+    pub max_stack: u32,
+}
+
+/// Every item the game knows about, by id. Loaded from [`ITEMS_PATH`]: a
+/// `[id]` header per item followed by `name = value` lines.
+#[derive(Resource, Default)]
+pub struct ItemDatabase {
+    items: HashMap<String, ItemDef>,
+}
+
+impl ItemDatabase {
+    pub fn get(&self, id: &str) -> Option<&ItemDef> {
+        self.items.get(id)
+    }
+
+    pub fn parse(config: &str) -> Self {
+        let mut database = Self::default();
+        // Fields of the item being read: (id, line it started on, name = value pairs)
+        let mut current: Option<(String, usize, Vec<(String, String)>)> = None;
+
+        for (line_number, line) in config.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(id) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                if let Some((id, start, fields)) = current.take() {
+                    database.insert(id, start, fields);
+                }
+                current = Some((id.trim().to_string(), line_number + 1, Vec::new()));
+                continue;
+            }
+
+            let Some((_, _, fields)) = current.as_mut() else {
+                warn!(
+                    "{ITEMS_PATH}:{}: expected an `[item_id]` header",
+                    line_number + 1
+                );
+                continue;
+            };
+            let Some((name, value)) = line.split_once('=') else {
+                warn!("{ITEMS_PATH}:{}: expected `name = value`", line_number + 1);
+                continue;
+            };
+            fields.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        if let Some((id, start, fields)) = current {
+            database.insert(id, start, fields);
+        }
+        database
+    }
+
+    fn insert(&mut self, id: String, line_number: usize, fields: Vec<(String, String)>) {
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let number = |name: &str| match field(name).map(str::parse::<f32>) {
+            Some(Ok(value)) => value,
+            Some(Err(_)) => {
+                warn!("{ITEMS_PATH}:{line_number}: [{id}] {name} is not a number");
+                0.0
+            }
+            None => 0.0,
+        };
+
+        let heal_time = field("heal_time").map_or(DEFAULT_HEAL_TIME, |_| number("heal_time"));
+        let kind = match field("kind") {
+            Some("consumable") => ItemKind::Consumable {
+                heal: number("heal"),
+                heal_time,
+            },
+            Some("flask") => ItemKind::Flask {
+                heal: number("heal"),
+                heal_time,
+                charges: number("charges").max(0.0) as u32,
+            },
+            Some("weapon") => ItemKind::Weapon {
+                damage: number("damage"),
+            },
+            Some("key") => ItemKind::Key,
+            other => {
+                warn!(
+                    "{ITEMS_PATH}:{line_number}: [{id}] has unknown kind {:?}",
+                    other.unwrap_or("")
+                );
+                return;
+            }
+        };
+        let max_stack = match field("max_stack").map(str::parse) {
+            Some(Ok(max_stack)) => max_stack,
+            _ if matches!(kind, ItemKind::Consumable { .. }) => 10,
+            _ => 1,
+        };
+
+        self.items.insert(
+            id.clone(),
+            ItemDef {
+                name: field("name").unwrap_or(&id).to_string(),
+                description: field("description").unwrap_or_default().to_string(),
+                id,
+                kind,
+                max_stack,
+            },
+        );
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(ITEMS_PATH) {
+            Ok(config) => {
+                let database = Self::parse(&config);
+                info!("Loaded {} items from {ITEMS_PATH}", database.items.len());
+                database
+            }
+            Err(err) => {
+                warn!("Couldn't read {ITEMS_PATH}: {err}");
+                Self::default()
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+mod components;
+mod consumables;
+mod items;
+mod systems;
+
+pub use components::*;
+pub use consumables::*;
+pub use items::*;
+pub use systems::*;
+
+use crate::gameplay::interaction::interact;
+use crate::states::AppState;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ItemDatabase::load())
+            .add_event::<InventoryChanged>()
+            .add_event::<UseItem>()
+            .add_event::<ItemUsed>()
+            .add_systems(
+                Update,
+                (
+                    init_pickups,
+                    collect_pickups
+                        .after(interact)
+                        .run_if(in_state(AppState::Playing)),
+                    (
+                        quick_heal.run_if(in_state(AppState::Playing)),
+                        // Items are also used from the inventory screen; a drink
+                        // started there plays out once it closes
+                        use_items
+                            .run_if(in_state(AppState::Playing).or(in_state(AppState::Inventory))),
+                        (update_item_use, apply_consumables, regenerate_health)
+                            .chain()
+                            .run_if(in_state(AppState::Playing)),
+                    )
+                        .chain(),
+                    refill_flasks,
+                    announce_inventory_changes,
+                ),
+            );
+    }
+}
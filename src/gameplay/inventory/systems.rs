@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::{Inventory, InventoryChanged, Pickup, UseItem};
+use super::consumables::{UsingItem, ITEM_USE_TIME};
+use super::items::{ItemDatabase, ItemKind};
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::interaction::{Interactable, Interacted};
+use crate::gameplay::player::{Dodging, Traversal};
+
+/// Makes new pickups interactable, prompting with the item's name.
+pub fn init_pickups(
+    mut commands: Commands,
+    items: Res<ItemDatabase>,
+    pickup_query: Query<(Entity, &Pickup), Added<Pickup>>,
+) {
+    for (entity, pickup) in pickup_query.iter() {
+        let name = items
+            .get(&pickup.item)
+            .map_or(pickup.item.as_str(), |item| item.name.as_str());
+        let prompt = if pickup.count > 1 {
+            format!("Pick up {name} ({})", pickup.count)
+        } else {
+            format!("Pick up {name}")
+        };
+        commands
+            .entity(entity)
+            .insert(Interactable::new(prompt).any_facing());
+    }
+}
+
+pub fn collect_pickups(
+    mut commands: Commands,
+    items: Res<ItemDatabase>,
+    mut interacted_events: EventReader<Interacted>,
+    mut pickup_query: Query<(&mut Pickup, &mut Interactable)>,
+    mut inventory_query: Query<&mut Inventory>,
+) {
+    for event in interacted_events.read() {
+        let Ok((mut pickup, mut interactable)) = pickup_query.get_mut(event.target) else {
+            continue;
+        };
+        let Ok(mut inventory) = inventory_query.get_mut(event.interactor) else {
+            continue;
+        };
+        let Some(item) = items.get(&pickup.item) else {
+            warn!("Pickup holds unknown item '{}'", pickup.item);
+            continue;
+        };
+
+        let leftover = inventory.add(item, pickup.count);
+        if leftover == pickup.count {
+            info!("No room for {}", item.name);
+            continue;
+        }
+        info!("Picked up {} x{}", item.name, pickup.count - leftover);
+        if leftover == 0 {
+            commands.entity(event.target).despawn_recursive();
+        } else {
+            pickup.count = leftover;
+            interactable.prompt = format!("Pick up {} ({leftover})", item.name);
+        }
+    }
+}
+
+/// Carries out [`UseItem`] requests: healing items start a drink (see
+/// `update_item_use`), weapons are equipped (or unequipped if already in hand),
+/// keys are only used by doors.
+pub fn use_items(
+    mut commands: Commands,
+    items: Res<ItemDatabase>,
+    mut use_events: EventReader<UseItem>,
+    mut owner_query: Query<(
+        &mut Inventory,
+        Option<&CombatStatus>,
+        Has<UsingItem>,
+        Has<Dodging>,
+        Has<Traversal>,
+        Option<&KinematicCharacterControllerOutput>,
+    )>,
+) {
+    for event in use_events.read() {
+        let Ok((mut inventory, combat_status, using_item, dodging, traversing, controller_output)) =
+            owner_query.get_mut(event.owner)
+        else {
+            continue;
+        };
+        if combat_status.is_some_and(|combat_status| combat_status.is_dead) {
+            continue;
+        }
+        let Some(item) = items.get(&event.item) else {
+            continue;
+        };
+        if !inventory.has(&item.id) {
+            continue;
+        }
+
+        match item.kind {
+            ItemKind::Consumable { .. } | ItemKind::Flask { .. } => {
+                let staggered = combat_status.is_some_and(|combat_status| combat_status.is_hit);
+                // Drinking roots the owner, which would leave them hanging in mid-air
+                let grounded = controller_output.is_some_and(|output| output.grounded);
+                if using_item || dodging || traversing || staggered || !grounded {
+                    continue;
+                }
+                if !inventory.can_use(item) {
+                    info!("{} is empty", item.name);
+                    continue;
+                }
+                commands.entity(event.owner).insert(UsingItem {
+                    item: item.id.clone(),
+                    timer: ITEM_USE_TIME,
+                });
+            }
+            ItemKind::Weapon { .. } => {
+                if inventory.equipped_weapon.as_deref() == Some(item.id.as_str()) {
+                    inventory.equipped_weapon = None;
+                    info!("Unequipped {}", item.name);
+                } else {
+                    inventory.equipped_weapon = Some(item.id.clone());
+                    info!("Equipped {}", item.name);
+                }
+            }
+            ItemKind::Key => info!("{} opens a locked door", item.name),
+        }
+    }
+}
+
+pub fn announce_inventory_changes(
+    inventory_query: Query<Entity, Changed<Inventory>>,
+    mut changed_events: EventWriter<InventoryChanged>,
+) {
+    for owner in inventory_query.iter() {
+        changed_events.send(InventoryChanged { owner });
+    }
+}
@@ -1,9 +1,25 @@
 pub mod ai;
+pub mod boss;
 pub mod combat;
+pub mod companion;
+pub mod difficulty;
 pub mod health;
+pub mod interaction;
+pub mod inventory;
+pub mod npc;
 pub mod player;
+pub mod respawn;
+pub mod spawner;
 
 pub use ai::AIPlugin;
+pub use boss::BossPlugin;
 pub use combat::CombatPlugin;
+pub use companion::CompanionPlugin;
+pub use difficulty::DifficultyPlugin;
 pub use health::HealthPlugin;
+pub use interaction::InteractionPlugin;
+pub use inventory::InventoryPlugin;
+pub use npc::NpcPlugin;
 pub use player::PlayerPlugin;
+pub use respawn::RespawnPlugin;
+pub use spawner::SpawnerPlugin;
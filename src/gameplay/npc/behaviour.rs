@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::*;
+use super::schedule::{GameClock, NpcSchedule};
+use crate::gameplay::ai::{Enemy, EnemyAi};
+use crate::gameplay::combat::{find_animation_entity, CombatStatus};
+use crate::gameplay::player::{CurrentAnimation, GameAnimations};
+
+const GRAVITY: f32 = -20.0;
+
+pub fn npc_behaviour(
+    time: Res<Time>,
+    clock: Res<GameClock>,
+    animations: Option<Res<GameAnimations>>,
+    enemy_query: Query<(&Transform, &EnemyAi, &CombatStatus), (With<Enemy>, Without<Npc>)>,
+    fighter_query: Query<(&Transform, &CombatStatus), (Without<Enemy>, Without<Npc>)>,
+    mut npc_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut NpcAi,
+            &mut KinematicCharacterController,
+            Option<&NpcSchedule>,
+        ),
+        With<Npc>,
+    >,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+) {
+    let Some(animations) = animations else {
+        return;
+    };
+
+    for (npc_entity, mut transform, mut ai, mut controller, schedule) in npc_query.iter_mut() {
+        let position = transform.translation;
+
+        // Any enemy in a fight, or anyone on our side swinging a sword, counts as combat
+        let threat = enemy_query
+            .iter()
+            .filter(|(_, enemy_ai, combat_status)| {
+                !combat_status.is_dead && enemy_ai.state.is_engaged()
+            })
+            .map(|(enemy_transform, _, _)| enemy_transform.translation)
+            .chain(
+                fighter_query
+                    .iter()
+                    .filter(|(_, combat_status)| combat_status.is_attacking)
+                    .map(|(fighter_transform, _)| fighter_transform.translation),
+            )
+            .filter(|threat_pos| threat_pos.distance(position) < NPC_ALARM_RADIUS)
+            .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+
+        let scheduled = schedule.and_then(|schedule| schedule.entry_at(clock.hour));
+        let (entry_index, location, activity, facing) = match scheduled {
+            Some((index, entry)) => (
+                Some(index),
+                entry.location,
+                entry.activity.as_deref(),
+                entry.facing,
+            ),
+            None => (None, ai.home_position, None, None),
+        };
+
+        if let Some(threat_pos) = threat {
+            ai.calm_timer = 0.0;
+            if !matches!(ai.state, NpcState::Flee | NpcState::Cower) {
+                // Run home if that leads away from the fight, otherwise straight away from it
+                let away = Vec3::new(position.x - threat_pos.x, 0.0, position.z - threat_pos.z)
+                    .normalize_or_zero();
+                let target = if ai.home_position.distance(threat_pos) > NPC_FLEE_DISTANCE {
+                    ai.home_position
+                } else {
+                    position + away * NPC_FLEE_DISTANCE
+                };
+                info!("NPC fleeing from combat");
+                ai.flee_target = Some(target);
+                ai.state = NpcState::Flee;
+            }
+        } else if matches!(ai.state, NpcState::Flee | NpcState::Cower) {
+            ai.calm_timer += time.delta_secs();
+            if ai.calm_timer > NPC_CALM_TIME {
+                ai.flee_target = None;
+                ai.state = NpcState::Travel;
+            }
+        }
+
+        if entry_index != ai.entry_index {
+            ai.entry_index = entry_index;
+            if ai.state == NpcState::Activity {
+                ai.state = NpcState::Travel;
+            }
+        }
+
+        let mut movement = Vec3::new(0.0, GRAVITY * time.delta_secs(), 0.0);
+        let mut walk_to = |goal: Vec3, speed: f32, transform: &mut Transform| -> bool {
+            let offset = Vec3::new(goal.x - position.x, 0.0, goal.z - position.z);
+            if offset.length() < NPC_ARRIVE_TOLERANCE {
+                return true;
+            }
+            let dir = offset.normalize();
+            movement.x = dir.x * speed * time.delta_secs();
+            movement.z = dir.z * speed * time.delta_secs();
+            transform.rotation = Quat::from_rotation_y(dir.x.atan2(dir.z));
+            false
+        };
+
+        let desired_anim = match ai.state {
+            NpcState::Travel => {
+                if walk_to(location, NPC_WALK_SPEED, &mut transform) {
+                    ai.state = NpcState::Activity;
+                }
+                Some(animations.walk_index)
+            }
+            NpcState::Activity => {
+                if let Some(yaw) = facing {
+                    transform.rotation = Quat::from_rotation_y(yaw);
+                }
+                activity
+                    .and_then(|name| animations.clips.get(name).copied())
+                    .or(Some(animations.idle_index))
+            }
+            NpcState::Flee => {
+                let target = ai.flee_target.unwrap_or(ai.home_position);
+                if walk_to(target, NPC_FLEE_SPEED, &mut transform) {
+                    ai.state = NpcState::Cower;
+                }
+                Some(animations.run_index)
+            }
+            NpcState::Cower => animations
+                .clips
+                .get(NPC_COWER_ANIMATION)
+                .copied()
+                .or(Some(animations.idle_index)),
+        };
+
+        controller.translation = Some(movement);
+
+        if let Some(anim_entity) = find_animation_entity(npc_entity, &children, &anim_query) {
+            if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
+                if current_anim.0 != desired_anim {
+                    anim_player.stop_all();
+                    if let Some(anim_index) = desired_anim {
+                        anim_player.play(anim_index).repeat();
+                    }
+                    current_anim.0 = desired_anim;
+                }
+            }
+        }
+    }
+}
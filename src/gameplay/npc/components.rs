@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+pub const NPC_WALK_SPEED: f32 = 0.35;
+pub const NPC_FLEE_SPEED: f32 = 1.2;
+/// Fighting this close to an NPC sends it running.
+pub const NPC_ALARM_RADIUS: f32 = 2.0;
+/// How far from the fight a fleeing NPC tries to get.
+pub const NPC_FLEE_DISTANCE: f32 = 2.5;
+/// Seconds without fighting nearby before a frightened NPC goes back to its routine.
+pub const NPC_CALM_TIME: f32 = 5.0;
+pub const NPC_ARRIVE_TOLERANCE: f32 = 0.1;
+pub const NPC_COWER_ANIMATION: &str = "Sit_Floor_Idle";
+
+/// A neutral character that follows an [`NpcSchedule`](super::NpcSchedule) and
+/// avoids combat.
+#[derive(Component)]
+#[require(NpcAi)]
+pub struct Npc;
+
+#[derive(Component, Default)]
+pub struct NpcAi {
+    pub state: NpcState,
+    /// Where the NPC lives; used when it has no schedule and as a safe spot to flee to.
+    pub home_position: Vec3,
+    pub entry_index: Option<usize>,
+    pub flee_target: Option<Vec3>,
+    pub calm_timer: f32,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum NpcState {
+    /// Walking to the current schedule location.
+    #[default]
+    Travel,
+    /// At the location, playing the scheduled activity.
+    Activity,
+    Flee,
+    /// Hiding after fleeing until things calm down.
+    Cower,
+}
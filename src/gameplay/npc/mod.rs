@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+mod behaviour;
+mod components;
+mod schedule;
+
+pub use behaviour::*;
+pub use components::*;
+pub use schedule::*;
+
+use crate::states::AppState;
+
+pub struct NpcPlugin;
+
+impl Plugin for NpcPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameClock>().add_systems(
+            Update,
+            (advance_clock, npc_behaviour)
+                .chain()
+                .run_if(in_state(AppState::Playing)),
+        );
+    }
+}
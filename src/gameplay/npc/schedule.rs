@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+pub const HOURS_PER_DAY: f32 = 24.0;
+/// Real seconds in one in-game day.
+pub const DAY_LENGTH_SECONDS: f32 = 600.0;
+pub const DAY_START_HOUR: f32 = 8.0;
+
+/// In-game time of day, in hours from midnight.
+#[derive(Resource)]
+pub struct GameClock {
+    pub hour: f32,
+    pub day_length: f32,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self {
+            hour: DAY_START_HOUR,
+            day_length: DAY_LENGTH_SECONDS,
+        }
+    }
+}
+
+pub fn advance_clock(time: Res<Time>, mut clock: ResMut<GameClock>) {
+    let hours = time.delta_secs() / clock.day_length * HOURS_PER_DAY;
+    clock.hour = (clock.hour + hours) % HOURS_PER_DAY;
+}
+
+#[derive(Clone)]
+pub struct ScheduleEntry {
+    pub start_hour: f32,
+    pub location: Vec3,
+    /// Knight.glb clip to play once there (e.g. "Sit_Chair_Idle"); idle if unset.
+    pub activity: Option<String>,
+    /// Yaw to face while doing the activity.
+    pub facing: Option<f32>,
+}
+
+impl ScheduleEntry {
+    pub fn new(start_hour: f32, location: Vec3) -> Self {
+        Self {
+            start_hour,
+            location,
+            activity: None,
+            facing: None,
+        }
+    }
+
+    pub fn with_activity(mut self, activity: impl Into<String>) -> Self {
+        self.activity = Some(activity.into());
+        self
+    }
+
+    pub fn facing(mut self, yaw: f32) -> Self {
+        self.facing = Some(yaw);
+        self
+    }
+}
+
+/// Daily routine, one entry per stop; each entry lasts until the next one starts.
+#[derive(Component, Clone, Default)]
+pub struct NpcSchedule {
+    pub entries: Vec<ScheduleEntry>,
+}
+
+impl NpcSchedule {
+    pub fn new(mut entries: Vec<ScheduleEntry>) -> Self {
+        entries.sort_by(|a, b| a.start_hour.total_cmp(&b.start_hour));
+        Self { entries }
+    }
+
+    /// The entry active at `hour`; before the first start time the last entry
+    /// carries over from the previous day.
+    pub fn entry_at(&self, hour: f32) -> Option<(usize, &ScheduleEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, entry)| entry.start_hour <= hour)
+            .or_else(|| self.entries.iter().enumerate().next_back())
+    }
+}
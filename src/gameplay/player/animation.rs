@@ -1,7 +1,10 @@
-use bevy::{gltf::Gltf, prelude::*};
+use bevy::{gltf::Gltf, prelude::*, utils::HashMap};
 
 use super::components::Player;
+use super::dodge::{DODGE_LEFT_ANIMATION, DODGE_RIGHT_ANIMATION};
 use crate::gameplay::ai::Enemy;
+use crate::gameplay::companion::Companion;
+use crate::gameplay::npc::Npc;
 
 pub const WALK_ANIMATION: &str = "Walking_A";
 pub const RUN_ANIMATION: &str = "Running_B";
@@ -26,6 +29,10 @@ pub struct GameAnimations {
     pub hit_index: AnimationNodeIndex,
     pub death_index: AnimationNodeIndex,
     pub block_index: AnimationNodeIndex,
+    pub dodge_left_index: AnimationNodeIndex,
+    pub dodge_right_index: AnimationNodeIndex,
+    /// Every clip in Knight.glb, keyed by its animation name.
+    pub clips: HashMap<String, AnimationNodeIndex>,
 }
 
 #[derive(Component)]
@@ -50,35 +57,37 @@ pub fn load_animations(
         return;
     };
 
-    let get_clip = |name: &str| -> Option<Handle<AnimationClip>> {
-        gltf.named_animations.get(name).cloned().or_else(|| {
+    // Every clip goes into the graph so data-driven content (patrol stops, NPC
+    // activities) can refer to any Knight.glb animation by name
+    let mut graph = AnimationGraph::new();
+    let clips: HashMap<String, AnimationNodeIndex> = gltf
+        .named_animations
+        .iter()
+        .map(|(name, clip)| (name.to_string(), graph.add_clip(clip.clone(), 1.0, graph.root)))
+        .collect();
+
+    let get_index = |name: &str| -> Option<AnimationNodeIndex> {
+        clips.get(name).copied().or_else(|| {
             warn!(
                 "Animation '{}' not found in Knight.glb. Available: {:?}",
                 name,
-                gltf.named_animations.keys().collect::<Vec<_>>()
+                clips.keys().collect::<Vec<_>>()
             );
             None
         })
     };
 
-    let Some(idle_clip) = get_clip(IDLE_ANIMATION) else { return };
-    let Some(walk_clip) = get_clip(WALK_ANIMATION) else { return };
-    let Some(run_clip) = get_clip(RUN_ANIMATION) else { return };
-    let Some(attack_clip) = get_clip(ATTACK_ANIMATION) else { return };
-    let Some(jump_clip) = get_clip(JUMP_ANIMATION) else { return };
-    let Some(hit_clip) = get_clip(HIT_ANIMATION) else { return };
-    let Some(death_clip) = get_clip(DEATH_ANIMATION) else { return };
-    let Some(block_clip) = get_clip(BLOCK_ANIMATION) else { return };
+    let Some(idle_index) = get_index(IDLE_ANIMATION) else { return };
+    let Some(walk_index) = get_index(WALK_ANIMATION) else { return };
+    let Some(run_index) = get_index(RUN_ANIMATION) else { return };
+    let Some(attack_index) = get_index(ATTACK_ANIMATION) else { return };
+    let Some(jump_index) = get_index(JUMP_ANIMATION) else { return };
+    let Some(hit_index) = get_index(HIT_ANIMATION) else { return };
+    let Some(death_index) = get_index(DEATH_ANIMATION) else { return };
+    let Some(block_index) = get_index(BLOCK_ANIMATION) else { return };
+    let Some(dodge_left_index) = get_index(DODGE_LEFT_ANIMATION) else { return };
+    let Some(dodge_right_index) = get_index(DODGE_RIGHT_ANIMATION) else { return };
 
-    let mut graph = AnimationGraph::new();
-    let idle_index = graph.add_clip(idle_clip, 1.0, graph.root);
-    let walk_index = graph.add_clip(walk_clip, 1.0, graph.root);
-    let run_index = graph.add_clip(run_clip, 1.0, graph.root);
-    let attack_index = graph.add_clip(attack_clip, 1.0, graph.root);
-    let jump_index = graph.add_clip(jump_clip, 1.0, graph.root);
-    let hit_index = graph.add_clip(hit_clip, 1.0, graph.root);
-    let death_index = graph.add_clip(death_clip, 1.0, graph.root);
-    let block_index = graph.add_clip(block_clip, 1.0, graph.root);
     let graph_handle = graphs.add(graph);
 
     info!("Loaded animations from Knight.glb");
@@ -93,6 +102,9 @@ pub fn load_animations(
         hit_index,
         death_index,
         block_index,
+        dodge_left_index,
+        dodge_right_index,
+        clips,
     });
 }
 
@@ -100,7 +112,7 @@ pub fn setup_character_animations(
     mut commands: Commands,
     animations: Option<Res<GameAnimations>>,
     mut anim_players: Query<(Entity, &mut AnimationPlayer), Without<AnimationSetupDone>>,
-    characters: Query<Entity, Or<(With<Player>, With<Enemy>)>>,
+    characters: Query<Entity, Or<(With<Player>, With<Enemy>, With<Companion>, With<Npc>)>>,
     children: Query<&Children>,
 ) {
     let Some(animations) = animations else {
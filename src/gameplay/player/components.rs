@@ -2,8 +2,10 @@ use bevy::prelude::*;
 
 pub const WALK_SPEED: f32 = 1.0;
 pub const RUN_SPEED: f32 = 2.0;
+pub const CROUCH_SPEED: f32 = 0.45;
 pub const GRAVITY: f32 = -4.0;
 pub const JUMP_VELOCITY: f32 = 2.0;
+pub const CHARACTER_SCALE: f32 = 0.2;
 pub const PLAYER_START: Vec3 = Vec3::new(0.0, 15.0, 0.0);
 
 #[derive(Component)]
@@ -12,5 +14,46 @@ pub struct Player;
 #[derive(Component, Default)]
 pub struct VerticalVelocity(pub f32);
 
+/// Ground-plane velocity carried between frames, so characters speed up,
+/// slow down and turn gradually instead of snapping to the input.
+#[derive(Component, Default)]
+pub struct HorizontalVelocity(pub Vec3);
+
+/// Per-character locomotion tuning.
+#[derive(Component, Clone)]
+pub struct MovementProfile {
+    pub walk_speed: f32,
+    pub run_speed: f32,
+    pub crouch_speed: f32,
+    /// Units/s² gained while there's movement input.
+    pub acceleration: f32,
+    /// Units/s² lost once input is released.
+    pub deceleration: f32,
+    /// How fast the direction of travel can swing round, in radians per second.
+    pub turn_rate: f32,
+    /// Fraction of acceleration, deceleration and turning available in the air.
+    pub air_control: f32,
+    /// Steepest slope that can be walked up, in radians.
+    pub max_slope_angle: f32,
+    /// Fraction of speed lost walking straight up a slope at `max_slope_angle`.
+    pub uphill_slowdown: f32,
+}
+
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self {
+            walk_speed: WALK_SPEED,
+            run_speed: RUN_SPEED,
+            crouch_speed: CROUCH_SPEED,
+            acceleration: 8.0,
+            deceleration: 10.0,
+            turn_rate: 10.0,
+            air_control: 0.3,
+            max_slope_angle: 45.0_f32.to_radians(),
+            uphill_slowdown: 0.4,
+        }
+    }
+}
+
 #[derive(Component, Default)]
 pub struct CurrentAnimation(pub Option<AnimationNodeIndex>);
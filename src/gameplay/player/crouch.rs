@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::components::{Player, CHARACTER_SCALE};
+use super::dodge::Dodging;
+use super::ledge::Traversal;
+use crate::core::input::{BufferedAction, PlayerInput};
+use crate::gameplay::combat::CombatStatus;
+
+/// How much of the capsule's half-segment is taken off while crouched.
+const CROUCH_HEIGHT_LOSS: f32 = 0.25 * CHARACTER_SCALE;
+
+/// Stand-ins: the Knight model has no crouch clips, so a wide stance and a slow walk.
+pub const CROUCH_IDLE_ANIMATION: &str = "2H_Melee_Idle";
+pub const CROUCH_WALK_ANIMATION: &str = "Walking_C";
+
+/// Present while the player is crouched: slower, shorter and harder for enemies to notice.
+#[derive(Component)]
+pub struct Crouching;
+
+pub fn standing_collider() -> Collider {
+    Collider::capsule_y(0.5 * CHARACTER_SCALE, 0.3 * CHARACTER_SCALE)
+}
+
+/// A shorter capsule shifted down so its bottom stays at the standing capsule's bottom.
+pub fn crouching_collider() -> Collider {
+    Collider::compound(vec![(
+        Vec3::NEG_Y * CROUCH_HEIGHT_LOSS,
+        Quat::IDENTITY,
+        Collider::capsule_y(
+            0.5 * CHARACTER_SCALE - CROUCH_HEIGHT_LOSS,
+            0.3 * CHARACTER_SCALE,
+        ),
+    )])
+}
+
+/// Crouches on the crouch toggle, and stands back up on the toggle or jump once
+/// there's headroom. Leaving the ground always stands the player up.
+pub fn toggle_crouch(
+    mut commands: Commands,
+    mut input: ResMut<PlayerInput>,
+    rapier_context: Query<&RapierContext>,
+    player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &CombatStatus,
+            Has<Crouching>,
+            Option<&KinematicCharacterControllerOutput>,
+        ),
+        (With<Player>, Without<Dodging>, Without<Traversal>),
+    >,
+) {
+    let Ok((entity, transform, combat_status, crouching, controller_output)) =
+        player_query.get_single()
+    else {
+        return;
+    };
+    if combat_status.is_dead {
+        return;
+    }
+    let grounded = controller_output.map(|o| o.grounded).unwrap_or(false);
+
+    if !crouching {
+        if input.crouch && grounded {
+            commands
+                .entity(entity)
+                .insert((Crouching, crouching_collider()));
+        }
+        return;
+    }
+
+    let jumping = input.buffered(BufferedAction::Jump);
+    if !input.crouch && !jumping && grounded {
+        return;
+    }
+    if jumping {
+        // Jumping out of a crouch only stands up
+        input.consume(BufferedAction::Jump);
+    }
+
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+    let blocked = context
+        .intersection_with_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            &standing_collider(),
+            QueryFilter::default()
+                .exclude_sensors()
+                .exclude_collider(entity),
+        )
+        .is_some();
+    if blocked {
+        return;
+    }
+    commands
+        .entity(entity)
+        .remove::<Crouching>()
+        .insert(standing_collider());
+}
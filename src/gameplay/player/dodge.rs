@@ -1,11 +1,12 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use crate::core::camera::PlayerYaw;
-use crate::core::input::PlayerInput;
+use crate::core::camera::{CameraMode, PlayerYaw};
+use crate::core::input::{BufferedAction, PlayerInput};
 use crate::gameplay::combat::{Stamina, DODGE_STAMINA_COST};
+use crate::gameplay::inventory::UsingItem;
 
-use super::{Player, VerticalVelocity};
+use super::{HardLanding, Player, Traversal, VerticalVelocity};
 
 pub const DODGE_SPEED: f32 = 3.0;
 pub const DODGE_DURATION: f32 = 0.4;
@@ -23,16 +24,27 @@ pub struct Dodging {
 
 pub fn handle_dodge(
     mut commands: Commands,
-    input: Res<PlayerInput>,
+    mut input: ResMut<PlayerInput>,
     player_yaw: Res<PlayerYaw>,
-    mut query: Query<(Entity, &mut Stamina, Option<&Dodging>), With<Player>>,
+    camera_mode: Res<CameraMode>,
+    mut query: Query<
+        (Entity, &Transform, &mut Stamina, Option<&Dodging>),
+        (
+            With<Player>,
+            Without<HardLanding>,
+            Without<Traversal>,
+            Without<UsingItem>,
+        ),
+    >,
 ) {
-    for (entity, mut stamina, maybe_dodging) in query.iter_mut() {
+    for (entity, transform, mut stamina, maybe_dodging) in query.iter_mut() {
         if maybe_dodging.is_some() {
             continue;
         }
 
-        if input.dodging && stamina.current >= DODGE_STAMINA_COST {
+        // A dodge pressed during the previous one stays queued until it ends
+        if input.buffered(BufferedAction::Dodge) && stamina.current >= DODGE_STAMINA_COST {
+            input.consume(BufferedAction::Dodge);
             stamina.current -= DODGE_STAMINA_COST;
 
             let yaw = player_yaw.0;
@@ -41,6 +53,9 @@ pub fn handle_dodge(
 
             let direction = if input.movement.length_squared() > 0.1 {
                 (forward * -input.movement.z + right * input.movement.x).normalize()
+            } else if *camera_mode == CameraMode::Free {
+                // No input: roll the way the character is facing, not the camera
+                (transform.rotation * Vec3::Z).with_y(0.0).normalize_or_zero()
             } else {
                 forward
             };
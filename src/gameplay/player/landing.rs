@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::animation::GameAnimations;
+use super::components::{HorizontalVelocity, Player};
+use super::CurrentAnimation;
+use crate::gameplay::combat::{find_animation_entity, CombatStatus};
+use crate::gameplay::difficulty::DifficultySettings;
+use crate::gameplay::health::Health;
+
+/// Falls shorter than this are free (a full jump peaks at 0.5).
+pub const SAFE_FALL_HEIGHT: f32 = 0.8;
+/// Damage per unit fallen beyond `SAFE_FALL_HEIGHT`.
+pub const FALL_DAMAGE_PER_UNIT: f32 = 60.0;
+/// Falls from this high kill outright.
+pub const LETHAL_FALL_HEIGHT: f32 = 3.0;
+/// Seconds the player is stuck recovering after a damaging fall.
+pub const HARD_LANDING_RECOVERY: f32 = 0.7;
+pub const HARD_LANDING_ANIMATION: &str = "Jump_Land";
+
+/// Remembers the highest point of the current fall.
+#[derive(Component, Default)]
+pub struct FallTracker {
+    pub airborne: bool,
+    pub peak_height: f32,
+    /// False until the first landing, so the drop in from the spawn point is free.
+    pub armed: bool,
+}
+
+/// Present while recovering from a hard landing; movement, jumping and dodging wait for it.
+#[derive(Component)]
+pub struct HardLanding {
+    pub timer: f32,
+}
+
+pub fn track_falls(
+    mut commands: Commands,
+    animations: Option<Res<GameAnimations>>,
+    difficulty: Res<DifficultySettings>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut FallTracker,
+            &mut Health,
+            &mut CombatStatus,
+            &mut HorizontalVelocity,
+            Option<&KinematicCharacterControllerOutput>,
+        ),
+        With<Player>,
+    >,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+) {
+    let Some(animations) = animations else {
+        return;
+    };
+
+    for (
+        entity,
+        transform,
+        mut tracker,
+        mut health,
+        mut combat_status,
+        mut horizontal_velocity,
+        controller_output,
+    ) in player_query.iter_mut()
+    {
+        let height = transform.translation.y;
+        let grounded = controller_output.map(|o| o.grounded).unwrap_or(false);
+
+        if !grounded {
+            if !tracker.airborne {
+                tracker.airborne = true;
+                tracker.peak_height = height;
+            }
+            tracker.peak_height = tracker.peak_height.max(height);
+            continue;
+        }
+
+        // Grounded: only the frame we touch down matters
+        if !tracker.airborne {
+            continue;
+        }
+        tracker.airborne = false;
+        let fall_height = tracker.peak_height - height;
+
+        if !tracker.armed {
+            tracker.armed = true;
+            continue;
+        }
+        if fall_height < SAFE_FALL_HEIGHT || combat_status.is_dead {
+            continue;
+        }
+
+        let damage = if fall_height >= LETHAL_FALL_HEIGHT {
+            health.current
+        } else {
+            (fall_height - SAFE_FALL_HEIGHT) * FALL_DAMAGE_PER_UNIT * difficulty.damage_taken
+        };
+        health.current = (health.current - damage).max(0.0);
+        horizontal_velocity.0 = Vec3::ZERO;
+        info!(
+            "Hard landing from {:.1}! Health: {}/{}",
+            fall_height, health.current, health.max
+        );
+
+        let anim = if health.current <= 0.0 {
+            combat_status.is_dead = true;
+            info!("Player died from the fall!");
+            animations.death_index
+        } else {
+            commands.entity(entity).insert(HardLanding {
+                timer: HARD_LANDING_RECOVERY,
+            });
+            animations
+                .clips
+                .get(HARD_LANDING_ANIMATION)
+                .copied()
+                .unwrap_or(animations.hit_index)
+        };
+
+        if let Some(anim_entity) = find_animation_entity(entity, &children, &anim_query) {
+            if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
+                anim_player.stop_all();
+                anim_player.play(anim);
+                current_anim.0 = Some(anim);
+            }
+        }
+    }
+}
+
+pub fn update_hard_landing(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut HardLanding)>,
+) {
+    for (entity, mut landing) in query.iter_mut() {
+        landing.timer -= time.delta_secs();
+        if landing.timer <= 0.0 {
+            commands.entity(entity).remove::<HardLanding>();
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::animation::GameAnimations;
+use super::components::{HorizontalVelocity, Player, VerticalVelocity, CHARACTER_SCALE};
+use super::crouch::Crouching;
+use super::landing::FallTracker;
+use super::CurrentAnimation;
+use crate::core::input::{BufferedAction, PlayerInput};
+use crate::gameplay::combat::{find_animation_entity, CombatStatus};
+
+/// Half the player's capsule height (0.5 half-segment + 0.3 radius, scaled).
+const CHARACTER_HALF_HEIGHT: f32 = 0.8 * CHARACTER_SCALE;
+const PROBE_RADIUS: f32 = 0.03;
+
+/// How far ahead of the character to look for a wall to grab or vault.
+pub const LEDGE_REACH: f32 = 0.15;
+/// A ledge can be grabbed when its top is between these heights above the character's origin.
+pub const LEDGE_GRAB_MIN: f32 = 0.05;
+pub const LEDGE_GRAB_MAX: f32 = 0.3;
+/// While hanging the character's origin sits this far below the ledge top...
+pub const HANG_DROP: f32 = 0.22;
+/// ...and this far out from the wall.
+pub const HANG_WALL_GAP: f32 = 0.07;
+pub const SHIMMY_SPEED: f32 = 0.4;
+pub const CLIMB_DURATION: f32 = 0.6;
+/// After letting go, ledges can't be grabbed again for this long.
+pub const LEDGE_REGRAB_COOLDOWN: f32 = 0.4;
+/// Obstacles whose top is between these heights above the feet can be vaulted.
+pub const VAULT_MIN_HEIGHT: f32 = 0.06;
+pub const VAULT_MAX_HEIGHT: f32 = 0.2;
+pub const VAULT_DISTANCE: f32 = 0.45;
+pub const VAULT_DURATION: f32 = 0.45;
+/// Extra height the vault arc clears the obstacle by.
+pub const VAULT_CLEARANCE: f32 = 0.05;
+
+pub const HANG_ANIMATION: &str = "Jump_Idle";
+pub const CLIMB_ANIMATION: &str = "Jump_Full_Short";
+pub const VAULT_ANIMATION: &str = "Jump_Full_Long";
+
+/// Ledge movement that takes over from `player_movement` while present.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum Traversal {
+    Hanging {
+        /// Horizontal normal of the wall, pointing away from it.
+        wall_normal: Vec3,
+    },
+    Climbing {
+        start: Vec3,
+        end: Vec3,
+        elapsed: f32,
+    },
+    Vaulting {
+        start: Vec3,
+        end: Vec3,
+        /// Height of the arc's peak above the straight line from start to end.
+        arc: f32,
+        elapsed: f32,
+    },
+}
+
+#[derive(Component)]
+pub struct LedgeGrabCooldown(pub f32);
+
+struct Ledge {
+    /// Point on the top edge of the ledge.
+    edge: Vec3,
+    wall_normal: Vec3,
+}
+
+/// Shape-cast helpers sharing the player's query filter.
+struct LedgeProbe<'a> {
+    context: &'a RapierContext,
+    filter: QueryFilter<'a>,
+    probe: Collider,
+}
+
+impl LedgeProbe<'_> {
+    fn cast(&self, origin: Vec3, direction: Vec3, distance: f32) -> Option<ShapeCastHit> {
+        self.context
+            .cast_shape(
+                origin,
+                Quat::IDENTITY,
+                direction,
+                &self.probe,
+                ShapeCastOptions::with_max_time_of_impact(distance),
+                self.filter,
+            )
+            .map(|(_, hit)| hit)
+    }
+
+    /// Looks for a wall ahead of `base` whose top lies between `min` and `max` above it.
+    fn find_ledge(&self, base: Vec3, direction: Vec3, min: f32, max: f32) -> Option<Ledge> {
+        let wall_hit = self.cast(base + Vec3::Y * min, direction, LEDGE_REACH)?;
+        let wall_normal = wall_hit
+            .details
+            .map(|details| -details.normal1)
+            .unwrap_or(-direction)
+            .with_y(0.0)
+            .normalize_or(-direction);
+        let wall_point = base + direction * (wall_hit.time_of_impact + PROBE_RADIUS);
+
+        // Drop a probe just past the wall face to find the top
+        let top_start =
+            wall_point.with_y(base.y + max + PROBE_RADIUS) - wall_normal * PROBE_RADIUS * 2.0;
+        let top_hit = self.cast(top_start, Vec3::NEG_Y, max - min)?;
+        if top_hit.status == ShapeCastStatus::PenetratingOrWithinTargetDist {
+            // Something's right above the edge: no room to hold on or climb
+            return None;
+        }
+        let top = top_start.y - top_hit.time_of_impact - PROBE_RADIUS;
+
+        Some(Ledge {
+            edge: wall_point.with_y(top),
+            wall_normal,
+        })
+    }
+
+    /// Whether the character's capsule fits with its origin at `position`.
+    fn fits(&self, position: Vec3) -> bool {
+        let body = Collider::capsule_y(0.5 * CHARACTER_SCALE, 0.3 * CHARACTER_SCALE);
+        self.context
+            .intersection_with_shape(position, Quat::IDENTITY, &body, self.filter)
+            .is_none()
+    }
+}
+
+fn face(transform: &mut Transform, direction: Vec3) {
+    transform.rotation = Quat::from_rotation_y(direction.x.atan2(direction.z));
+}
+
+pub fn ledge_traversal(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut input: ResMut<PlayerInput>,
+    animations: Option<Res<GameAnimations>>,
+    rapier_context: Query<&RapierContext>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut VerticalVelocity,
+            &mut HorizontalVelocity,
+            &mut FallTracker,
+            &CombatStatus,
+            Option<&KinematicCharacterControllerOutput>,
+            Option<&mut Traversal>,
+            Option<&mut LedgeGrabCooldown>,
+        ),
+        (With<Player>, Without<Crouching>),
+    >,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+) {
+    let Some(animations) = animations else {
+        return;
+    };
+    let Ok(context) = rapier_context.get_single() else {
+        return;
+    };
+    let Ok((
+        entity,
+        mut transform,
+        mut vertical_velocity,
+        mut horizontal_velocity,
+        mut fall_tracker,
+        combat_status,
+        controller_output,
+        traversal,
+        cooldown,
+    )) = player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    let probe = LedgeProbe {
+        context,
+        filter: QueryFilter::default()
+            .exclude_sensors()
+            .exclude_collider(entity),
+        probe: Collider::ball(PROBE_RADIUS),
+    };
+    let delta = time.delta_secs();
+    let mut play = |anim: Option<AnimationNodeIndex>, repeat: bool| {
+        let Some(anim) = anim else {
+            return;
+        };
+        if let Some(anim_entity) = find_animation_entity(entity, &children, &anim_query) {
+            if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
+                if current_anim.0 != Some(anim) {
+                    anim_player.stop_all();
+                    let active = anim_player.play(anim);
+                    if repeat {
+                        active.repeat();
+                    }
+                    current_anim.0 = Some(anim);
+                }
+            }
+        }
+    };
+
+    let cooling_down = cooldown.is_some();
+    if let Some(mut cooldown) = cooldown {
+        cooldown.0 -= delta;
+        if cooldown.0 <= 0.0 {
+            commands.entity(entity).remove::<LedgeGrabCooldown>();
+        }
+    }
+
+    let Some(mut traversal) = traversal else {
+        if combat_status.is_dead {
+            return;
+        }
+        let grounded = controller_output.map(|o| o.grounded).unwrap_or(false);
+        let speed = horizontal_velocity.0.length();
+        if speed < 0.1 {
+            return;
+        }
+        let direction = horizontal_velocity.0 / speed;
+        let position = transform.translation;
+
+        if grounded {
+            // Vault: jump while running at a low obstacle
+            if !input.buffered(BufferedAction::Jump) {
+                return;
+            }
+            let feet = position - Vec3::Y * CHARACTER_HALF_HEIGHT;
+            let Some(obstacle) =
+                probe.find_ledge(feet, direction, VAULT_MIN_HEIGHT, VAULT_MAX_HEIGHT)
+            else {
+                return;
+            };
+            let rise = obstacle.edge.y - feet.y;
+            let peak = obstacle.edge
+                + direction * 0.1
+                + Vec3::Y * (CHARACTER_HALF_HEIGHT + VAULT_CLEARANCE);
+            let end = (position + direction * VAULT_DISTANCE) + Vec3::Y * rise;
+            if !probe.fits(peak) || !probe.fits(end) {
+                return;
+            }
+
+            input.consume(BufferedAction::Jump);
+            face(&mut transform, direction);
+            commands.entity(entity).insert(Traversal::Vaulting {
+                start: position,
+                end,
+                arc: (peak.y - position.y.max(end.y)).max(0.0),
+                elapsed: 0.0,
+            });
+            play(animations.clips.get(VAULT_ANIMATION).copied(), false);
+            return;
+        }
+
+        // Grab: falling towards a ledge at hand height
+        if cooling_down || vertical_velocity.0 > 0.0 || input.movement == Vec3::ZERO {
+            return;
+        }
+        let Some(ledge) = probe.find_ledge(position, direction, LEDGE_GRAB_MIN, LEDGE_GRAB_MAX)
+        else {
+            return;
+        };
+        let hang_position = ledge.edge + ledge.wall_normal * HANG_WALL_GAP - Vec3::Y * HANG_DROP;
+        if !probe.fits(hang_position) {
+            return;
+        }
+
+        info!("Grabbed a ledge");
+        transform.translation = hang_position;
+        face(&mut transform, -ledge.wall_normal);
+        vertical_velocity.0 = 0.0;
+        horizontal_velocity.0 = Vec3::ZERO;
+        commands.entity(entity).insert(Traversal::Hanging {
+            wall_normal: ledge.wall_normal,
+        });
+        play(animations.clips.get(HANG_ANIMATION).copied(), true);
+        return;
+    };
+
+    // Falls are measured from wherever the ledge move leaves us
+    fall_tracker.peak_height = transform.translation.y;
+
+    match *traversal {
+        Traversal::Hanging { wall_normal } => {
+            let let_go = input.buffered(BufferedAction::Dodge) || input.movement.z > 0.5;
+            if let_go || combat_status.is_dead {
+                input.consume(BufferedAction::Dodge);
+                commands
+                    .entity(entity)
+                    .remove::<Traversal>()
+                    .insert(LedgeGrabCooldown(LEDGE_REGRAB_COOLDOWN));
+                return;
+            }
+
+            let into_wall = -wall_normal;
+            let climb = input.buffered(BufferedAction::Jump) || input.movement.z < -0.5;
+            if climb {
+                if let Some(ledge) = probe.find_ledge(
+                    transform.translation,
+                    into_wall,
+                    HANG_DROP - 0.05,
+                    HANG_DROP + 0.05,
+                ) {
+                    let stand =
+                        ledge.edge + into_wall * 0.12 + Vec3::Y * (CHARACTER_HALF_HEIGHT + 0.02);
+                    if probe.fits(stand) {
+                        input.consume(BufferedAction::Jump);
+                        *traversal = Traversal::Climbing {
+                            start: transform.translation,
+                            end: stand,
+                            elapsed: 0.0,
+                        };
+                        play(animations.clips.get(CLIMB_ANIMATION).copied(), false);
+                        return;
+                    }
+                }
+            }
+
+            // Shimmy sideways as long as there's still a ledge to hold on to
+            let right = into_wall.cross(Vec3::Y);
+            let step = right * input.movement.x * SHIMMY_SPEED * delta;
+            if step != Vec3::ZERO {
+                let next = transform.translation + step;
+                if let Some(ledge) =
+                    probe.find_ledge(next, into_wall, HANG_DROP - 0.05, HANG_DROP + 0.05)
+                {
+                    let hang_position =
+                        ledge.edge + ledge.wall_normal * HANG_WALL_GAP - Vec3::Y * HANG_DROP;
+                    if probe.fits(hang_position) {
+                        transform.translation = hang_position;
+                        face(&mut transform, -ledge.wall_normal);
+                        *traversal = Traversal::Hanging {
+                            wall_normal: ledge.wall_normal,
+                        };
+                    }
+                }
+            }
+        }
+        Traversal::Climbing {
+            start,
+            end,
+            ref mut elapsed,
+        } => {
+            *elapsed += delta;
+            let t = (*elapsed / CLIMB_DURATION).min(1.0);
+            // Pull up first, then step forward onto the ledge
+            let rise = t.sqrt();
+            let forward = t * t;
+            transform.translation = Vec3::new(
+                start.x.lerp(end.x, forward),
+                start.y.lerp(end.y, rise),
+                start.z.lerp(end.z, forward),
+            );
+            if t >= 1.0 {
+                vertical_velocity.0 = 0.0;
+                commands.entity(entity).remove::<Traversal>();
+            }
+        }
+        Traversal::Vaulting {
+            start,
+            end,
+            arc,
+            ref mut elapsed,
+        } => {
+            *elapsed += delta;
+            let t = (*elapsed / VAULT_DURATION).min(1.0);
+            transform.translation =
+                start.lerp(end, t) + Vec3::Y * arc * (t * std::f32::consts::PI).sin();
+            if t >= 1.0 {
+                // Carry on running out of the vault
+                vertical_velocity.0 = 0.0;
+                horizontal_velocity.0 = (end - start).with_y(0.0) / VAULT_DURATION;
+                commands.entity(entity).remove::<Traversal>();
+            }
+        }
+    }
+}
@@ -2,12 +2,18 @@ use bevy::prelude::*;
 
 mod animation;
 mod components;
+mod crouch;
 mod dodge;
+mod landing;
+mod ledge;
 mod movement;
 
 pub use animation::*;
 pub use components::*;
+pub use crouch::*;
 pub use dodge::*;
+pub use landing::*;
+pub use ledge::*;
 pub use movement::*;
 
 use crate::states::AppState;
@@ -21,7 +27,18 @@ impl Plugin for PlayerPlugin {
             (
                 load_animations,
                 setup_character_animations,
-                (handle_dodge, update_dodge, player_movement)
+                // Chained: these all consume buffered input, and traversal/crouch have to
+                // claim a jump before movement does or a vault can turn into a plain jump
+                (
+                    toggle_crouch,
+                    ledge_traversal,
+                    handle_dodge,
+                    update_dodge,
+                    player_movement,
+                    track_falls,
+                    update_hard_landing,
+                )
+                    .chain()
                     .run_if(in_state(AppState::Playing)),
             ),
         );
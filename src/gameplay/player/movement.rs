@@ -1,48 +1,134 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
-use super::components::{Player, VerticalVelocity, GRAVITY, JUMP_VELOCITY, RUN_SPEED, WALK_SPEED};
+use super::components::{
+    HorizontalVelocity, MovementProfile, Player, VerticalVelocity, GRAVITY, JUMP_VELOCITY,
+};
 use super::animation::GameAnimations;
+use super::crouch::{Crouching, CROUCH_IDLE_ANIMATION, CROUCH_WALK_ANIMATION};
 use super::dodge::Dodging;
+use super::landing::HardLanding;
+use super::ledge::Traversal;
 use super::CurrentAnimation;
-use crate::core::camera::PlayerYaw;
-use crate::core::input::PlayerInput;
+use crate::core::camera::{CameraMode, LockOnTarget, PlayerYaw};
+use crate::core::input::{BufferedAction, PlayerInput};
 use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::inventory::UsingItem;
+
+/// Below this speed the character counts as standing still.
+const IDLE_SPEED: f32 = 0.05;
+/// How far below the character's origin to look for the ground's slope.
+const GROUND_PROBE_DISTANCE: f32 = 0.3;
+
+/// Moves `current` towards `target` by at most `max_delta`.
+fn approach(current: Vec3, target: Vec3, max_delta: f32) -> Vec3 {
+    let offset = target - current;
+    if offset.length() <= max_delta {
+        target
+    } else {
+        current + offset.normalize() * max_delta
+    }
+}
+
+/// Swings the flat direction `heading` towards `target` by at most `max_angle` radians.
+fn turn_towards(heading: Vec3, target: Vec3, max_angle: f32) -> Vec3 {
+    let from = heading.x.atan2(heading.z);
+    let to = target.x.atan2(target.z);
+    let diff = (to - from + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+        - std::f32::consts::PI;
+    let angle = from + diff.clamp(-max_angle, max_angle);
+    Vec3::new(angle.sin(), 0.0, angle.cos())
+}
+
+/// Accelerates, decelerates and turns `velocity` towards `target`. `control`
+/// scales every rate (1.0 on the ground, `air_control` in the air).
+fn steer_velocity(
+    velocity: Vec3,
+    target: Vec3,
+    profile: &MovementProfile,
+    control: f32,
+    delta: f32,
+) -> Vec3 {
+    if target == Vec3::ZERO {
+        return approach(velocity, Vec3::ZERO, profile.deceleration * control * delta);
+    }
+
+    let speed = velocity.length();
+    let turned = if speed > IDLE_SPEED {
+        turn_towards(velocity, target, profile.turn_rate * control * delta) * speed
+    } else {
+        velocity
+    };
+    approach(turned, target, profile.acceleration * control * delta)
+}
+
+/// Tilts a flat velocity onto the ground plane so the controller follows the slope,
+/// slowing it down when heading uphill.
+fn follow_slope(velocity: Vec3, normal: Vec3, profile: &MovementProfile) -> Vec3 {
+    let speed = velocity.length();
+    if speed == 0.0 {
+        return velocity;
+    }
+    let along = (velocity - normal * velocity.dot(normal)).normalize_or_zero();
+    let climb = along.y.max(0.0) / profile.max_slope_angle.sin();
+    along * speed * (1.0 - profile.uphill_slowdown * climb.min(1.0))
+}
 
 pub fn player_movement(
-    keyboard: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
+    rapier_context: Query<&RapierContext>,
     player_yaw: Res<PlayerYaw>,
+    camera_mode: Res<CameraMode>,
+    lock_on: Res<LockOnTarget>,
     mut input: ResMut<PlayerInput>,
     animations: Option<Res<GameAnimations>>,
     mut player_query: Query<
         (
-            &Transform,
+            &mut Transform,
             &mut KinematicCharacterController,
             &mut VerticalVelocity,
+            &mut HorizontalVelocity,
+            &MovementProfile,
             Option<&KinematicCharacterControllerOutput>,
             &CombatStatus,
             Option<&Dodging>,
+            Option<&HardLanding>,
+            Has<Crouching>,
+            Has<UsingItem>,
         ),
-        With<Player>,
+        (With<Player>, Without<Traversal>),
     >,
     children: Query<&Children>,
     player_entity_query: Query<Entity, With<Player>>,
     mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
 ) {
-    let Ok((_transform, mut controller, mut vertical_velocity, controller_output, combat_state, maybe_dodging)) =
-        player_query.get_single_mut()
+    let Ok((
+        mut transform,
+        mut controller,
+        mut vertical_velocity,
+        mut horizontal_velocity,
+        profile,
+        controller_output,
+        combat_state,
+        maybe_dodging,
+        maybe_hard_landing,
+        crouching,
+        using_item,
+    )) = player_query.get_single_mut()
     else {
         return;
     };
 
     if combat_state.is_dead {
-        input.consume_attack(); // Clear any buffered attacks on death
+        input.consume_buffered(); // Clear any buffered actions on death
+        horizontal_velocity.0 = Vec3::ZERO;
         return;
     }
 
-    // Skip movement while dodging (dodge system handles movement)
-    if maybe_dodging.is_some() {
+    // Skip movement while dodging (dodge system handles movement), picking
+    // ourselves up after a fall or drinking; recover from a standstill
+    if maybe_dodging.is_some() || maybe_hard_landing.is_some() || using_item {
+        horizontal_velocity.0 = Vec3::ZERO;
         return;
     }
 
@@ -71,23 +157,31 @@ pub fn player_movement(
 
     let is_jumping = current_anim.0 == Some(animations.jump_index) && !anim_player.all_finished();
 
+    // Jump is checked first so a queued jump wins over an attack when both are allowed
+    if input.buffered(BufferedAction::Jump)
+        && grounded
+        && !crouching
+        && !is_attacking
+        && !is_jumping
+    {
+        input.consume(BufferedAction::Jump);
+        vertical_velocity.0 = JUMP_VELOCITY;
+        anim_player.stop_all();
+        anim_player.play(animations.jump_index);
+        current_anim.0 = Some(animations.jump_index);
+    }
+
     // Use buffered attack input - allows attacks in air and with slight input delay tolerance
-    if input.attack_buffered() && !is_attacking && !is_jumping {
-        input.consume_attack();
+    if input.buffered(BufferedAction::Attack) && !is_attacking && !is_jumping {
+        input.consume(BufferedAction::Attack);
         anim_player.stop_all();
         anim_player.play(animations.attack_index);
         current_anim.0 = Some(animations.attack_index);
         return;
     }
 
-    if keyboard.just_pressed(KeyCode::Space) && grounded && !is_attacking && !is_jumping {
-        vertical_velocity.0 = JUMP_VELOCITY;
-        anim_player.stop_all();
-        anim_player.play(animations.jump_index);
-        current_anim.0 = Some(animations.jump_index);
-    }
-
     if is_attacking {
+        horizontal_velocity.0 = Vec3::ZERO;
         return;
     }
 
@@ -95,48 +189,96 @@ pub fn player_movement(
     let forward = Vec3::new(-yaw.sin(), 0.0, -yaw.cos());
     let right = Vec3::new(forward.z, 0.0, -forward.x);
 
-    let mut direction = Vec3::ZERO;
-    if keyboard.pressed(KeyCode::KeyW) {
-        direction += forward;
-    }
-    if keyboard.pressed(KeyCode::KeyS) {
-        direction -= forward;
-    }
-    if keyboard.pressed(KeyCode::KeyA) {
-        direction += right;
-    }
-    if keyboard.pressed(KeyCode::KeyD) {
-        direction -= right;
-    }
+    // input.movement is -z forward / +x right; `right` here points to the camera's left
+    let direction = forward * -input.movement.z - right * input.movement.x;
+
+    let is_running = input.sprinting && !crouching;
+    // A half-pushed stick walks at half speed; keys always give full walking speed
+    let target_speed = if is_running {
+        profile.run_speed
+    } else if crouching {
+        profile.crouch_speed * input.movement.length().min(1.0)
+    } else {
+        profile.walk_speed * input.movement.length().min(1.0)
+    };
+    let target = direction.normalize_or_zero() * target_speed;
 
-    let is_moving = direction != Vec3::ZERO;
-    let is_running = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    let speed = if is_running { RUN_SPEED } else { WALK_SPEED };
+    let control = if grounded { 1.0 } else { profile.air_control };
+    horizontal_velocity.0 = steer_velocity(
+        horizontal_velocity.0,
+        target,
+        profile,
+        control,
+        time.delta_secs(),
+    );
+    let speed = horizontal_velocity.0.length();
 
-    let mut movement = Vec3::ZERO;
-    if is_moving {
-        direction = direction.normalize();
-        movement = direction * speed * time.delta_secs();
+    if *camera_mode == CameraMode::Free {
+        // Strafe facing the camera (and so any lock-on target) while blocking or
+        // locked on, otherwise face the direction of travel
+        let facing = if combat_state.is_blocking || lock_on.0.is_some() {
+            Some(forward)
+        } else if speed > IDLE_SPEED {
+            Some(horizontal_velocity.0 / speed)
+        } else {
+            None
+        };
+        if let Some(facing) = facing {
+            let current = transform.rotation * Vec3::Z;
+            let turned = turn_towards(current, facing, profile.turn_rate * time.delta_secs());
+            transform.rotation = Quat::from_rotation_y(turned.x.atan2(turned.z));
+        }
     }
 
+    controller.max_slope_climb_angle = profile.max_slope_angle;
+    controller.min_slope_slide_angle = profile.max_slope_angle;
+
+    let ground_normal = rapier_context.get_single().ok().and_then(|context| {
+        context
+            .cast_ray_and_get_normal(
+                transform.translation,
+                Vec3::NEG_Y,
+                GROUND_PROBE_DISTANCE,
+                true,
+                QueryFilter::default()
+                    .exclude_sensors()
+                    .exclude_collider(player_entity),
+            )
+            .map(|(_, hit)| hit.normal)
+    });
+
+    let mut movement = match ground_normal {
+        Some(normal) if grounded => follow_slope(horizontal_velocity.0, normal, profile),
+        _ => horizontal_velocity.0,
+    } * time.delta_secs();
+
     if grounded && vertical_velocity.0 <= 0.0 {
         vertical_velocity.0 = 0.0;
     } else {
         vertical_velocity.0 += GRAVITY * time.delta_secs();
     }
-    movement.y = vertical_velocity.0 * time.delta_secs();
+    movement.y += vertical_velocity.0 * time.delta_secs();
 
     controller.translation = Some(movement);
 
     if grounded && !is_jumping {
-        let desired_anim = if is_moving {
-            Some(if is_running {
-                animations.run_index
+        // Pick the clip from the actual speed so it follows acceleration,
+        // and play it faster or slower to match
+        let (desired_anim, clip_speed) = if crouching {
+            if speed < IDLE_SPEED {
+                (animations.clips.get(CROUCH_IDLE_ANIMATION).copied(), 1.0)
             } else {
-                animations.walk_index
-            })
+                (
+                    animations.clips.get(CROUCH_WALK_ANIMATION).copied(),
+                    speed / profile.crouch_speed,
+                )
+            }
+        } else if speed < IDLE_SPEED {
+            (None, 1.0)
+        } else if speed > (profile.walk_speed + profile.run_speed) * 0.5 {
+            (Some(animations.run_index), speed / profile.run_speed)
         } else {
-            None
+            (Some(animations.walk_index), speed / profile.walk_speed)
         };
 
         if current_anim.0 != desired_anim {
@@ -146,5 +288,8 @@ pub fn player_movement(
             }
             current_anim.0 = desired_anim;
         }
+        if let Some(active) = desired_anim.and_then(|index| anim_player.animation_mut(index)) {
+            active.set_speed(clip_speed.clamp(0.5, 1.5));
+        }
     }
 }
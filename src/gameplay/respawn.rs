@@ -0,0 +1,267 @@
+use bevy::prelude::*;
+
+use crate::core::input::PlayerInput;
+use crate::gameplay::ai::{AiState, Enemy, EnemyAi};
+use crate::gameplay::combat::{find_animation_entity, CombatStatus, Stamina};
+use crate::gameplay::health::Health;
+use crate::gameplay::inventory::{HealOverTime, UsingItem};
+use crate::gameplay::player::{
+    standing_collider, Crouching, CurrentAnimation, Dodging, FallTracker, HardLanding,
+    HorizontalVelocity, LedgeGrabCooldown, Player, Traversal, VerticalVelocity, PLAYER_START,
+};
+use crate::states::AppState;
+
+/// Seconds the death animation plays before the game-over screen comes up.
+pub const GAME_OVER_DELAY: f32 = 2.5;
+pub const CHECKPOINT_RADIUS: f32 = 0.4;
+/// How far above or below a checkpoint the player can be and still reach it.
+const CHECKPOINT_HEIGHT_TOLERANCE: f32 = 0.5;
+
+/// Touching this makes it the place the player comes back to after dying.
+#[derive(Component)]
+pub struct Checkpoint {
+    pub radius: f32,
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self {
+            radius: CHECKPOINT_RADIUS,
+        }
+    }
+}
+
+/// Where the player respawns. `checkpoint` is `None` until one has been reached,
+/// in which case it's the start position.
+#[derive(Resource)]
+pub struct ActiveCheckpoint {
+    pub checkpoint: Option<Entity>,
+    pub position: Vec3,
+}
+
+impl Default for ActiveCheckpoint {
+    fn default() -> Self {
+        Self {
+            checkpoint: None,
+            position: PLAYER_START,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct RespawnSettings {
+    /// Also send surviving enemies home at full health when the player respawns.
+    pub reset_enemies: bool,
+}
+
+impl Default for RespawnSettings {
+    fn default() -> Self {
+        Self {
+            reset_enemies: true,
+        }
+    }
+}
+
+/// Sent when the player steps onto a checkpoint, whether or not it was already active.
+#[derive(Event)]
+pub struct CheckpointReached {
+    pub checkpoint: Entity,
+}
+
+#[derive(Event)]
+pub struct PlayerRespawned {
+    pub position: Vec3,
+    pub enemies_reset: bool,
+}
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveCheckpoint>()
+            .init_resource::<RespawnSettings>()
+            .add_event::<CheckpointReached>()
+            .add_event::<PlayerRespawned>()
+            .add_systems(
+                Update,
+                (activate_checkpoints, enter_game_over).run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnExit(AppState::GameOver), respawn_player);
+    }
+}
+
+fn activate_checkpoints(
+    mut active: ResMut<ActiveCheckpoint>,
+    mut standing_on: Local<Option<Entity>>,
+    player_query: Query<(&Transform, &CombatStatus), With<Player>>,
+    checkpoint_query: Query<(Entity, &Transform, &Checkpoint)>,
+    mut reached_events: EventWriter<CheckpointReached>,
+) {
+    let Ok((player_transform, combat_status)) = player_query.get_single() else {
+        return;
+    };
+    if combat_status.is_dead {
+        return;
+    }
+    let player_pos = player_transform.translation;
+
+    let touching = checkpoint_query
+        .iter()
+        .find(|(_, transform, checkpoint)| {
+            let offset = player_pos - transform.translation;
+            offset.with_y(0.0).length() < checkpoint.radius
+                && offset.y.abs() < CHECKPOINT_HEIGHT_TOLERANCE
+        })
+        .map(|(entity, _, _)| entity);
+    // Only stepping onto a checkpoint counts, not standing on it
+    if touching == *standing_on {
+        return;
+    }
+    *standing_on = touching;
+    let Some(entity) = touching else {
+        return;
+    };
+
+    if active.checkpoint != Some(entity) {
+        // Respawn where the player actually stood, so it's known to be clear ground
+        active.checkpoint = Some(entity);
+        active.position = player_pos;
+        info!("Checkpoint reached");
+    }
+    reached_events.send(CheckpointReached { checkpoint: entity });
+}
+
+/// Lets the death animation play out, then hands over to the game-over screen.
+fn enter_game_over(
+    time: Res<Time>,
+    mut since_death: Local<f32>,
+    player_query: Query<&CombatStatus, With<Player>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Ok(combat_status) = player_query.get_single() else {
+        return;
+    };
+    if !combat_status.is_dead {
+        *since_death = 0.0;
+        return;
+    }
+
+    *since_death += time.delta_secs();
+    if *since_death >= GAME_OVER_DELAY {
+        *since_death = 0.0;
+        next_state.set(AppState::GameOver);
+    }
+}
+
+fn respawn_player(
+    mut commands: Commands,
+    active: Res<ActiveCheckpoint>,
+    settings: Res<RespawnSettings>,
+    mut input: ResMut<PlayerInput>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Health,
+            &mut Stamina,
+            &mut CombatStatus,
+            &mut VerticalVelocity,
+            &mut HorizontalVelocity,
+            &mut FallTracker,
+        ),
+        (With<Player>, Without<Enemy>),
+    >,
+    mut enemy_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Health,
+            &mut Stamina,
+            &mut CombatStatus,
+            &mut EnemyAi,
+        ),
+        (With<Enemy>, Without<Player>),
+    >,
+    children: Query<&Children>,
+    mut anim_query: Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+    mut respawned_events: EventWriter<PlayerRespawned>,
+) {
+    let Ok((
+        entity,
+        mut transform,
+        mut health,
+        mut stamina,
+        mut combat_status,
+        mut vertical_velocity,
+        mut horizontal_velocity,
+        mut fall_tracker,
+    )) = player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    transform.translation = active.position;
+    health.current = health.max;
+    stamina.current = stamina.max;
+    *combat_status = CombatStatus::default();
+    vertical_velocity.0 = 0.0;
+    horizontal_velocity.0 = Vec3::ZERO;
+    // The settle onto the ground after respawning shouldn't count as a fall
+    *fall_tracker = FallTracker::default();
+    input.consume_buffered();
+    commands
+        .entity(entity)
+        .remove::<(
+            Dodging,
+            HardLanding,
+            Traversal,
+            LedgeGrabCooldown,
+            Crouching,
+            UsingItem,
+            HealOverTime,
+        )>()
+        .insert(standing_collider());
+    reset_animation(entity, &children, &mut anim_query);
+
+    if settings.reset_enemies {
+        for (enemy, mut transform, mut health, mut stamina, mut combat_status, mut ai) in
+            enemy_query.iter_mut()
+        {
+            // The dead stay dead; only survivors are put back on their posts
+            if combat_status.is_dead {
+                continue;
+            }
+            transform.translation = ai.home_position;
+            health.current = health.max;
+            stamina.current = stamina.max;
+            *combat_status = CombatStatus::default();
+            ai.state = AiState::Idle;
+            ai.state_timer = 0.0;
+            ai.patrol_target = None;
+            ai.has_attack_token = false;
+            ai.threat_timer = 0.0;
+            ai.reacted_to_attack = false;
+            commands.entity(enemy).remove::<Dodging>();
+            reset_animation(enemy, &children, &mut anim_query);
+        }
+    }
+
+    info!("Player respawned");
+    respawned_events.send(PlayerRespawned {
+        position: active.position,
+        enemies_reset: settings.reset_enemies,
+    });
+}
+
+fn reset_animation(
+    character: Entity,
+    children: &Query<&Children>,
+    anim_query: &mut Query<(&mut AnimationPlayer, &mut CurrentAnimation)>,
+) {
+    if let Some(anim_entity) = find_animation_entity(character, children, anim_query) {
+        if let Ok((mut anim_player, mut current_anim)) = anim_query.get_mut(anim_entity) {
+            anim_player.stop_all();
+            current_anim.0 = None;
+        }
+    }
+}
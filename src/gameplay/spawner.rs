@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+
+use crate::core::rng::{GameRng, RngStream};
+use crate::gameplay::ai::{spawn_enemy, AssignedRoute, Enemy, EnemyStats};
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::health::Health;
+use crate::states::AppState;
+
+/// One enemy variant a spawner can produce, picked with probability proportional to `weight`.
+#[derive(Clone)]
+pub struct SpawnEntry {
+    pub weight: f32,
+    pub health: f32,
+    pub stats: EnemyStats,
+    pub route: Option<String>,
+}
+
+impl Default for SpawnEntry {
+    fn default() -> Self {
+        Self {
+            weight: 1.0,
+            health: 100.0,
+            stats: EnemyStats::default(),
+            route: None,
+        }
+    }
+}
+
+/// Keeps up to `max_alive` enemies from its table alive around its transform.
+#[derive(Component)]
+pub struct EnemySpawner {
+    pub table: Vec<SpawnEntry>,
+    pub max_alive: usize,
+    pub respawn_delay: f32,
+    pub spawn_radius: f32,
+    /// How many more enemies may be spawned; `None` respawns forever.
+    /// Wave spawners start at `Some(0)` and are topped up by [`WaveSequence`].
+    pub remaining: Option<u32>,
+    pub timer: f32,
+    pub alive: Vec<Entity>,
+}
+
+impl Default for EnemySpawner {
+    fn default() -> Self {
+        Self {
+            table: vec![SpawnEntry::default()],
+            max_alive: 1,
+            respawn_delay: 10.0,
+            spawn_radius: 0.0,
+            remaining: None,
+            timer: 0.0,
+            alive: Vec::new(),
+        }
+    }
+}
+
+impl EnemySpawner {
+    fn pick_entry(&self, rng: &mut GameRng) -> Option<&SpawnEntry> {
+        let total: f32 = self.table.iter().map(|entry| entry.weight).sum();
+        let mut roll = rng.unit(RngStream::Spawner) * total;
+        for entry in &self.table {
+            if roll < entry.weight {
+                return Some(entry);
+            }
+            roll -= entry.weight;
+        }
+        self.table.last()
+    }
+}
+
+pub struct WaveSpawn {
+    pub spawner: Entity,
+    pub count: u32,
+}
+
+pub struct Wave {
+    pub spawns: Vec<WaveSpawn>,
+    /// Pause after the previous wave is cleared (or the game starts) before this one begins.
+    pub start_delay: f32,
+}
+
+/// Runs waves in order: wave N starts once every enemy of wave N-1 is dead.
+#[derive(Resource, Default)]
+pub struct WaveSequence {
+    pub waves: Vec<Wave>,
+    pub current: Option<usize>,
+    pub next: usize,
+    pub delay_timer: f32,
+}
+
+impl WaveSequence {
+    pub fn new(waves: Vec<Wave>) -> Self {
+        let delay_timer = waves.first().map(|wave| wave.start_delay).unwrap_or(0.0);
+        Self {
+            waves,
+            current: None,
+            next: 0,
+            delay_timer,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct WaveStarted {
+    pub wave: usize,
+}
+
+#[derive(Event)]
+pub struct WaveCleared {
+    pub wave: usize,
+    pub last: bool,
+}
+
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaveSequence>()
+            .add_event::<WaveStarted>()
+            .add_event::<WaveCleared>()
+            .add_systems(
+                Update,
+                (run_waves, run_spawners)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+fn run_spawners(
+    mut commands: Commands,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut rng: ResMut<GameRng>,
+    mut spawner_query: Query<(&Transform, &mut EnemySpawner)>,
+    enemy_query: Query<&CombatStatus, With<Enemy>>,
+) {
+    for (spawner_transform, mut spawner) in spawner_query.iter_mut() {
+        spawner.alive.retain(|enemy| {
+            enemy_query
+                .get(*enemy)
+                .is_ok_and(|combat_status| !combat_status.is_dead)
+        });
+
+        if spawner.alive.len() >= spawner.max_alive || spawner.remaining == Some(0) {
+            continue;
+        }
+
+        spawner.timer -= time.delta_secs();
+        if spawner.timer > 0.0 {
+            continue;
+        }
+
+        let Some(entry) = spawner.pick_entry(&mut rng).cloned() else {
+            continue;
+        };
+
+        let angle = rng.unit(RngStream::Spawner) * std::f32::consts::TAU;
+        let distance = rng.unit(RngStream::Spawner) * spawner.spawn_radius;
+        let position =
+            spawner_transform.translation + Vec3::new(angle.cos(), 0.0, angle.sin()) * distance;
+
+        let mut enemy = spawn_enemy(&mut commands, &asset_server, position);
+        enemy.insert((
+            Health {
+                current: entry.health,
+                max: entry.health,
+            },
+            entry.stats,
+        ));
+        if let Some(route) = entry.route {
+            enemy.insert(AssignedRoute::new(route));
+        }
+        let enemy_entity = enemy.id();
+
+        spawner.alive.push(enemy_entity);
+        spawner.timer = spawner.respawn_delay;
+        if let Some(remaining) = spawner.remaining.as_mut() {
+            *remaining -= 1;
+        }
+    }
+}
+
+fn run_waves(
+    time: Res<Time>,
+    mut waves: ResMut<WaveSequence>,
+    mut spawner_query: Query<&mut EnemySpawner>,
+    mut started_events: EventWriter<WaveStarted>,
+    mut cleared_events: EventWriter<WaveCleared>,
+) {
+    if let Some(current) = waves.current {
+        let cleared = waves.waves[current].spawns.iter().all(|spawn| {
+            spawner_query.get(spawn.spawner).map_or(true, |spawner| {
+                spawner.remaining == Some(0) && spawner.alive.is_empty()
+            })
+        });
+        if !cleared {
+            return;
+        }
+
+        let last = current + 1 >= waves.waves.len();
+        info!("Wave {} cleared", current + 1);
+        cleared_events.send(WaveCleared {
+            wave: current,
+            last,
+        });
+        waves.current = None;
+        waves.next = current + 1;
+        waves.delay_timer = waves
+            .waves
+            .get(waves.next)
+            .map(|wave| wave.start_delay)
+            .unwrap_or(0.0);
+    }
+
+    let next = waves.next;
+    if next >= waves.waves.len() {
+        return;
+    }
+
+    waves.delay_timer -= time.delta_secs();
+    if waves.delay_timer > 0.0 {
+        return;
+    }
+
+    for spawn in &waves.waves[next].spawns {
+        if let Ok(mut spawner) = spawner_query.get_mut(spawn.spawner) {
+            spawner.remaining = Some(spawner.remaining.unwrap_or(0) + spawn.count);
+            spawner.timer = 0.0;
+        }
+    }
+    info!("Wave {} started", next + 1);
+    started_events.send(WaveStarted { wave: next });
+    waves.current = Some(next);
+}
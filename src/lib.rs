@@ -3,13 +3,21 @@ pub mod gameplay;
 pub mod states;
 pub mod visual;
 
-pub use core::{CameraPlugin, InputPlugin};
-pub use gameplay::{AIPlugin, CombatPlugin, HealthPlugin, PlayerPlugin};
+pub use core::{CameraPlugin, InputPlugin, ReplayPlugin, RngPlugin};
+pub use gameplay::{
+    AIPlugin, BossPlugin, CombatPlugin, CompanionPlugin, DifficultyPlugin, HealthPlugin,
+    InteractionPlugin, InventoryPlugin, NpcPlugin, PlayerPlugin, RespawnPlugin, SpawnerPlugin,
+};
 pub use states::AppState;
-pub use visual::{HealthBarPlugin, ParticlePlugin};
+pub use visual::{
+    AiDebugPlugin, BossHealthBarPlugin, ControlsMenuPlugin, DifficultyMenuPlugin, GameOverPlugin,
+    HealthBarPlugin, InteractionPromptPlugin, InventoryScreenPlugin, ParticlePlugin,
+};
 
 pub use gameplay::player::{KnightGltf, Player, PLAYER_START};
 pub use gameplay::ai::{Enemy, EnemyAi};
+pub use gameplay::boss::Boss;
+pub use gameplay::companion::Companion;
 pub use gameplay::health::Health;
 pub use gameplay::combat::{CombatStatus, Stamina};
 pub use core::camera::{FollowCamera, PlayerYaw};
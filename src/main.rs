@@ -5,15 +5,29 @@ use bevy_rapier3d::prelude::*;
 
 use threegame::{
     states::AppState,
-    core::{InputPlugin, CameraPlugin},
-    gameplay::{PlayerPlugin, CombatPlugin, AIPlugin, HealthPlugin},
-    visual::{ParticlePlugin, HealthBarPlugin},
-    Player, Enemy, EnemyAi, Health, CombatStatus, Stamina, FollowCamera,
-    KnightGltf, PLAYER_START,
+    core::{InputPlugin, CameraPlugin, RngPlugin, ReplayPlugin},
+    gameplay::{
+        PlayerPlugin, CombatPlugin, AIPlugin, HealthPlugin, BossPlugin, SpawnerPlugin,
+        DifficultyPlugin, CompanionPlugin, NpcPlugin, RespawnPlugin, InteractionPlugin,
+        InventoryPlugin,
+    },
+    visual::{
+        ParticlePlugin, HealthBarPlugin, BossHealthBarPlugin, AiDebugPlugin, DifficultyMenuPlugin,
+        ControlsMenuPlugin, GameOverPlugin, InteractionPromptPlugin, InventoryScreenPlugin,
+    },
+    gameplay::ai::{spawn_enemy, EnemyStats},
+    gameplay::npc::{Npc, NpcAi, NpcSchedule, ScheduleEntry},
+    gameplay::player::{GameAnimations, CHARACTER_SCALE},
+    gameplay::spawner::{EnemySpawner, SpawnEntry, Wave, WaveSequence, WaveSpawn},
+    gameplay::boss::{ArenaExit, BossArena, BossBrain, BossPhase, MoveSet, BOSS_SCALE},
+    gameplay::respawn::Checkpoint,
+    gameplay::interaction::{Door, Interactable, Lever, LEVER_THROW_ANGLE},
+    gameplay::inventory::{Inventory, Pickup},
+    Player, Boss, Health, CombatStatus, Stamina, FollowCamera,
+    KnightGltf, Companion, PLAYER_START,
 };
 
 const CASTLE_SCALE: f32 = 2.0;
-const CHARACTER_SCALE: f32 = 0.2;
 
 fn main() {
     App::new()
@@ -27,23 +41,47 @@ fn main() {
         }))
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
         .init_state::<AppState>()
+        .add_plugins((RngPlugin, InputPlugin, ReplayPlugin, CameraPlugin))
         .add_plugins((
-            InputPlugin,
-            CameraPlugin,
             PlayerPlugin,
             CombatPlugin,
             AIPlugin,
             HealthPlugin,
+            BossPlugin,
+            SpawnerPlugin,
+            DifficultyPlugin,
+            CompanionPlugin,
+            NpcPlugin,
+            RespawnPlugin,
+            InteractionPlugin,
+            InventoryPlugin,
+        ))
+        .add_plugins((
             ParticlePlugin,
             HealthBarPlugin,
+            BossHealthBarPlugin,
+            AiDebugPlugin,
+            DifficultyMenuPlugin,
+            ControlsMenuPlugin,
+            GameOverPlugin,
+            InteractionPromptPlugin,
+            InventoryScreenPlugin,
         ))
         .add_systems(Startup, (setup, grab_cursor))
-        .add_systems(OnEnter(AppState::Loading), transition_to_playing)
+        .add_systems(Update, finish_loading.run_if(in_state(AppState::Loading)))
         .run();
 }
 
-fn transition_to_playing(mut next_state: ResMut<NextState<AppState>>) {
-    next_state.set(AppState::Playing);
+/// Holds the game in `Loading` until the animations are built and the castle's
+/// colliders exist, so gameplay (and any replay) starts on a fully loaded world.
+fn finish_loading(
+    animations: Option<Res<GameAnimations>>,
+    pending_colliders: Query<(), With<AsyncSceneCollider>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if animations.is_some() && pending_colliders.is_empty() {
+        next_state.set(AppState::Playing);
+    }
 }
 
 fn grab_cursor(mut windows: Query<&mut Window>) {
@@ -112,7 +150,11 @@ fn setup(
         Health { current: 200.0, max: 200.0 },
         Stamina::default(),
         CombatStatus::default(),
+        Inventory::default(),
         threegame::gameplay::player::VerticalVelocity::default(),
+        threegame::gameplay::player::HorizontalVelocity::default(),
+        threegame::gameplay::player::MovementProfile::default(),
+        threegame::gameplay::player::FallTracker::default(),
         RigidBody::KinematicPositionBased,
         Collider::capsule_y(0.5 * CHARACTER_SCALE, 0.3 * CHARACTER_SCALE),
         KinematicCharacterController {
@@ -121,24 +163,52 @@ fn setup(
         },
     ));
 
-    let enemy_positions = [
-        Vec3::new(-3.0, 15.0, -3.0),
-        Vec3::new(2.0, 15.0, -2.0),
-        Vec3::new(-2.0, 15.0, 2.0),
+    let companion_start = PLAYER_START + Vec3::new(0.5, 0.0, 0.3);
+    commands.spawn((
+        SceneRoot(asset_server.load("models/Knight.glb#Scene0")),
+        Transform::from_translation(companion_start)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::PI))
+            .with_scale(Vec3::splat(CHARACTER_SCALE)),
+        Companion,
+        Health { current: 150.0, max: 150.0 },
+        CombatStatus::default(),
+        RigidBody::KinematicPositionBased,
+        Collider::capsule_y(0.5 * CHARACTER_SCALE, 0.3 * CHARACTER_SCALE),
+        KinematicCharacterController {
+            snap_to_ground: Some(CharacterLength::Absolute(0.1)),
+            ..default()
+        },
+    ));
+
+    // Castle staff going about their day
+    let npc_schedules = [
+        NpcSchedule::new(vec![
+            ScheduleEntry::new(6.0, Vec3::new(1.0, 15.0, 1.5)).with_activity("Interact"),
+            ScheduleEntry::new(12.0, Vec3::new(-0.5, 15.0, 1.8))
+                .with_activity("Sit_Floor_Idle")
+                .facing(std::f32::consts::PI),
+            ScheduleEntry::new(14.0, Vec3::new(1.0, 15.0, 1.5)).with_activity("Use_Item"),
+            ScheduleEntry::new(21.0, Vec3::new(1.5, 15.0, 2.0)).with_activity("Lie_Idle"),
+        ]),
+        NpcSchedule::new(vec![
+            ScheduleEntry::new(7.0, Vec3::new(-1.0, 15.0, -1.0)),
+            ScheduleEntry::new(9.0, Vec3::new(0.5, 15.0, -1.5)).with_activity("PickUp"),
+            ScheduleEntry::new(13.0, Vec3::new(-1.0, 15.0, -1.0)).with_activity("Cheer"),
+            ScheduleEntry::new(20.0, Vec3::new(-1.5, 15.0, -0.5)).with_activity("Sit_Floor_Idle"),
+        ]),
     ];
 
-    for pos in enemy_positions {
+    for schedule in npc_schedules {
+        let home = schedule.entries.last().map(|entry| entry.location).unwrap_or(PLAYER_START);
         commands.spawn((
             SceneRoot(asset_server.load("models/Knight.glb#Scene0")),
-            Transform::from_translation(pos)
-                .with_scale(Vec3::splat(CHARACTER_SCALE)),
-            Enemy,
-            EnemyAi {
-                home_position: pos,
+            Transform::from_translation(home).with_scale(Vec3::splat(CHARACTER_SCALE)),
+            Npc,
+            NpcAi {
+                home_position: home,
                 ..default()
             },
-            Health::default(),
-            CombatStatus::default(),
+            schedule,
             RigidBody::KinematicPositionBased,
             Collider::capsule_y(0.5 * CHARACTER_SCALE, 0.3 * CHARACTER_SCALE),
             KinematicCharacterController {
@@ -148,6 +218,236 @@ fn setup(
         ));
     }
 
+    // Guards posted around the keep; each spawns once and stays dead
+    let guard_posts = [
+        (Vec3::new(-3.0, 15.0, -3.0), None),
+        (Vec3::new(2.0, 15.0, -2.0), Some("north_wall")),
+        (Vec3::new(-2.0, 15.0, 2.0), None),
+    ];
+
+    for (pos, route) in guard_posts {
+        commands.spawn((
+            Transform::from_translation(pos),
+            EnemySpawner {
+                table: vec![SpawnEntry {
+                    route: route.map(String::from),
+                    ..default()
+                }],
+                remaining: Some(1),
+                ..default()
+            },
+        ));
+    }
+
+    // Horde mode: courtyard spawners fed by an escalating wave sequence
+    if std::env::args().any(|arg| arg == "--horde") {
+        let brute = SpawnEntry {
+            weight: 0.3,
+            health: 160.0,
+            stats: EnemyStats {
+                chase_speed: 0.6,
+                attack_damage: 30.0,
+                attack_duration: 1.4,
+                ..default()
+            },
+            ..default()
+        };
+        let skirmisher = SpawnEntry {
+            weight: 0.7,
+            health: 70.0,
+            stats: EnemyStats {
+                chase_speed: 1.0,
+                dodge_chance: 0.4,
+                ..default()
+            },
+            ..default()
+        };
+
+        let horde_spawners: Vec<Entity> = [Vec3::new(-3.0, 15.0, 3.0), Vec3::new(3.0, 15.0, 3.0)]
+            .into_iter()
+            .map(|pos| {
+                commands
+                    .spawn((
+                        Transform::from_translation(pos),
+                        EnemySpawner {
+                            table: vec![brute.clone(), skirmisher.clone()],
+                            max_alive: 3,
+                            respawn_delay: 2.0,
+                            spawn_radius: 0.5,
+                            remaining: Some(0),
+                            ..default()
+                        },
+                    ))
+                    .id()
+            })
+            .collect();
+
+        let waves = (1..=5)
+            .map(|wave| Wave {
+                spawns: horde_spawners
+                    .iter()
+                    .map(|&spawner| WaveSpawn {
+                        spawner,
+                        count: wave + 1,
+                    })
+                    .collect(),
+                start_delay: 5.0,
+            })
+            .collect();
+        commands.insert_resource(WaveSequence::new(waves));
+    }
+
+    // Checkpoints: one in the keep, one just outside the boss arena
+    let checkpoint_mesh = meshes.add(Cylinder::new(0.08, 0.3));
+    let checkpoint_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.9, 0.7, 0.2),
+        emissive: LinearRgba::rgb(0.9, 0.6, 0.1),
+        ..default()
+    });
+    for pos in [Vec3::new(1.0, 15.0, -1.0), Vec3::new(-1.0, 15.0, 4.0)] {
+        commands.spawn((
+            Mesh3d(checkpoint_mesh.clone()),
+            MeshMaterial3d(checkpoint_material.clone()),
+            Transform::from_translation(pos),
+            Checkpoint::default(),
+        ));
+    }
+
+    // A door opened by hand, and a gate worked from a lever beside it
+    let door_mesh = meshes.add(Cuboid::new(0.5, 0.5, 0.06));
+    let door_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.45, 0.3, 0.15),
+        ..default()
+    });
+    let mut spawn_door = |hinge: Vec3, yaw: f32| {
+        let rotation = Quat::from_rotation_y(yaw);
+        commands
+            .spawn((
+                Transform::from_translation(hinge).with_rotation(rotation),
+                Visibility::default(),
+                RigidBody::KinematicPositionBased,
+                Door::new(rotation),
+            ))
+            .with_children(|door| {
+                door.spawn((
+                    Mesh3d(door_mesh.clone()),
+                    MeshMaterial3d(door_material.clone()),
+                    Transform::from_xyz(0.25, 0.25, 0.0),
+                    Collider::cuboid(0.25, 0.25, 0.03),
+                ));
+            })
+            .id()
+    };
+    let door = spawn_door(Vec3::new(-2.0, 15.0, -0.5), 0.0);
+    let gate = spawn_door(Vec3::new(-2.0, 15.0, 0.5), std::f32::consts::PI);
+    commands.entity(door).insert((
+        Door::new(Quat::IDENTITY).locked_with("storeroom_key"),
+        Interactable::new("Open door").with_offset(Vec3::new(0.25, 0.25, 0.0)),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(0.04, 0.25, 0.04))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.3, 0.35))),
+        Transform::from_xyz(-1.6, 15.0, 0.9)
+            .with_rotation(Quat::from_rotation_x(-LEVER_THROW_ANGLE)),
+        Collider::cuboid(0.02, 0.125, 0.02),
+        Interactable::new("Pull lever"),
+        Lever {
+            targets: vec![gate],
+            pulled: false,
+        },
+    ));
+
+    // Items to find: a flask, potions and a sword near the start, and the key to the locked door
+    let pickups = [
+        ("healing_flask", 1, Vec3::new(0.3, 15.0, -0.4), Color::srgb(0.9, 0.55, 0.1)),
+        ("health_potion", 2, Vec3::new(0.6, 15.0, -0.8), Color::srgb(0.8, 0.1, 0.15)),
+        ("iron_sword", 1, Vec3::new(-0.6, 15.0, -0.8), Color::srgb(0.7, 0.7, 0.75)),
+        ("storeroom_key", 1, Vec3::new(1.4, 15.0, 0.6), Color::srgb(0.85, 0.75, 0.3)),
+    ];
+    let pickup_mesh = meshes.add(Cuboid::new(0.08, 0.08, 0.08));
+    for (item, count, pos, color) in pickups {
+        commands.spawn((
+            Mesh3d(pickup_mesh.clone()),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_translation(pos + Vec3::Y * 0.04),
+            Pickup {
+                item: item.to_string(),
+                count,
+            },
+        ));
+    }
+
+    let arena_center = Vec3::new(-4.0, 15.0, 6.0);
+    let boss = spawn_enemy(&mut commands, &asset_server, arena_center)
+        .insert((
+            Transform::from_translation(arena_center)
+                .with_scale(Vec3::splat(CHARACTER_SCALE * BOSS_SCALE)),
+            Boss::new(
+                "The Castellan",
+                vec![
+                    BossPhase {
+                        health_threshold: 1.0,
+                        move_set: MoveSet {
+                            chase_speed: 0.7,
+                            attack_damage: 25.0,
+                            attack_duration: 1.2,
+                        },
+                        brain: BossBrain::Duelist,
+                    },
+                    BossPhase {
+                        health_threshold: 0.6,
+                        move_set: MoveSet {
+                            chase_speed: 0.9,
+                            attack_damage: 30.0,
+                            attack_duration: 1.0,
+                        },
+                        brain: BossBrain::Evasive,
+                    },
+                    BossPhase {
+                        health_threshold: 0.3,
+                        move_set: MoveSet {
+                            chase_speed: 1.2,
+                            attack_damage: 35.0,
+                            attack_duration: 0.7,
+                        },
+                        brain: BossBrain::Berserker,
+                    },
+                ],
+            ),
+            Health { current: 400.0, max: 400.0 },
+        ))
+        .id();
+
+    let arena = commands
+        .spawn((Transform::from_translation(arena_center), BossArena::new(boss)))
+        .id();
+
+    // Barriers across the arena entrances, raised while the fight is on
+    let barrier_mesh = meshes.add(Cuboid::new(1.2, 1.0, 0.1));
+    let barrier_material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.8, 0.2, 0.1, 0.4),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    for (offset, yaw) in [
+        (Vec3::new(0.0, 0.5, 2.5), 0.0),
+        (Vec3::new(2.5, 0.5, 0.0), std::f32::consts::FRAC_PI_2),
+    ] {
+        commands.spawn((
+            Mesh3d(barrier_mesh.clone()),
+            MeshMaterial3d(barrier_material.clone()),
+            Transform::from_translation(arena_center + offset)
+                .with_rotation(Quat::from_rotation_y(yaw)),
+            Visibility::Hidden,
+            RigidBody::Fixed,
+            Collider::cuboid(0.6, 0.5, 0.05),
+            ColliderDisabled,
+            ArenaExit { arena },
+        ));
+    }
+
     commands.spawn((
         Camera3d::default(),
         Transform::from_xyz(0.0, PLAYER_START.y + 1.0, 2.0).looking_at(PLAYER_START, Vec3::Y),
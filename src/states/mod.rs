@@ -7,6 +7,10 @@ pub enum AppState {
     Menu,
     Playing,
     Paused,
+    /// The player has died; waiting on the game-over screen to respawn.
+    GameOver,
+    /// The inventory screen is open; the world is paused behind it.
+    Inventory,
 }
 
 #[derive(SubStates, Default, Clone, Copy, Eq, PartialEq, Debug, Hash)]
@@ -0,0 +1,318 @@
+use bevy::color::palettes::css;
+use bevy::prelude::*;
+use std::fmt::Write;
+
+use crate::core::camera::FollowCamera;
+use crate::gameplay::ai::{
+    AiState, AssignedRoute, Enemy, EnemyAi, PatrolRoutes, ENEMY_ATTACK_RANGE,
+    ENEMY_CROUCH_DETECTION_RANGE, ENEMY_DETECTION_RANGE, ENEMY_HEARING_RANGE, ENEMY_LEASH_RADIUS,
+    ENEMY_VIEW_CONE_COS,
+};
+use crate::gameplay::combat::{CombatStatus, Stamina};
+use crate::gameplay::health::Health;
+use crate::gameplay::player::Player;
+
+pub const AI_DEBUG_TOGGLE_KEY: KeyCode = KeyCode::F3;
+const LABEL_HEIGHT: f32 = 0.55;
+
+#[derive(Resource, Default)]
+pub struct AiDebugSettings {
+    pub enabled: bool,
+}
+
+/// Screen-space state label that follows an enemy while the overlay is on.
+#[derive(Component)]
+pub struct AiDebugLabel {
+    pub enemy: Entity,
+}
+
+/// Top-right panel listing every enemy's blackboard values.
+#[derive(Component)]
+pub struct AiDebugPanel;
+
+pub struct AiDebugPlugin;
+
+impl Plugin for AiDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AiDebugSettings>()
+            .add_systems(Startup, setup_ai_debug_panel)
+            .add_systems(
+                Update,
+                (
+                    toggle_ai_debug,
+                    draw_ai_gizmos,
+                    sync_ai_debug_labels,
+                    update_ai_debug_labels,
+                    update_ai_debug_panel,
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn state_color(state: AiState) -> Color {
+    match state {
+        AiState::Idle => css::GRAY.into(),
+        AiState::Patrol => css::LIGHT_BLUE.into(),
+        AiState::Chase => css::ORANGE.into(),
+        AiState::Circle => css::GOLD.into(),
+        AiState::Attack => css::RED.into(),
+        AiState::Block => css::STEEL_BLUE.into(),
+        AiState::Dodge => css::VIOLET.into(),
+        AiState::Retreat => css::LIME.into(),
+        AiState::Return => css::WHITE.into(),
+    }
+}
+
+fn toggle_ai_debug(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<AiDebugSettings>,
+    mut panel_query: Query<&mut Visibility, With<AiDebugPanel>>,
+) {
+    if !keyboard.just_pressed(AI_DEBUG_TOGGLE_KEY) {
+        return;
+    }
+
+    settings.enabled = !settings.enabled;
+    for mut visibility in panel_query.iter_mut() {
+        *visibility = if settings.enabled {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn draw_ai_gizmos(
+    mut gizmos: Gizmos,
+    settings: Res<AiDebugSettings>,
+    routes: Res<PatrolRoutes>,
+    enemy_query: Query<(&Transform, &EnemyAi, &CombatStatus, Option<&AssignedRoute>), With<Enemy>>,
+) {
+    if !settings.enabled {
+        return;
+    }
+
+    // Circle gizmos lie in the XY plane; tip them flat onto the ground
+    let flat = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+
+    for (transform, ai, combat_status, assigned_route) in enemy_query.iter() {
+        if combat_status.is_dead {
+            continue;
+        }
+
+        let position = transform.translation;
+        let color = state_color(ai.state);
+
+        gizmos.circle(
+            Isometry3d::new(position, flat),
+            ENEMY_DETECTION_RANGE,
+            css::YELLOW.with_alpha(0.4),
+        );
+        gizmos.circle(
+            Isometry3d::new(position, flat),
+            ENEMY_ATTACK_RANGE,
+            css::RED,
+        );
+        gizmos.circle(
+            Isometry3d::new(position, flat),
+            ENEMY_HEARING_RANGE,
+            css::ORANGE.with_alpha(0.4),
+        );
+        gizmos.circle(
+            Isometry3d::new(ai.home_position, flat),
+            ENEMY_LEASH_RADIUS,
+            css::DIM_GRAY.with_alpha(0.4),
+        );
+
+        // The view cone, out to where a crouched player gets spotted
+        let forward = transform.rotation * Vec3::Z;
+        let half_angle = ENEMY_VIEW_CONE_COS.acos();
+        let left = Quat::from_rotation_y(half_angle) * forward;
+        let right = Quat::from_rotation_y(-half_angle) * forward;
+        gizmos.line(
+            position,
+            position + left * ENEMY_CROUCH_DETECTION_RANGE,
+            color,
+        );
+        gizmos.line(
+            position,
+            position + right * ENEMY_CROUCH_DETECTION_RANGE,
+            color,
+        );
+        gizmos.line(
+            position + left * ENEMY_CROUCH_DETECTION_RANGE,
+            position + right * ENEMY_CROUCH_DETECTION_RANGE,
+            color,
+        );
+
+        if let Some(target) = ai.patrol_target {
+            gizmos.arrow(position, target, css::LIGHT_BLUE);
+        }
+        if ai.state == AiState::Return {
+            gizmos.arrow(position, ai.home_position, css::WHITE);
+        }
+        if ai.has_attack_token {
+            gizmos.sphere(
+                Isometry3d::from_translation(position + Vec3::Y * 0.45),
+                0.03,
+                css::RED,
+            );
+        }
+
+        if let Some(route) = assigned_route.and_then(|assigned| routes.get(&assigned.name)) {
+            gizmos.linestrip(
+                route.waypoints.iter().map(|waypoint| waypoint.position),
+                css::SKY_BLUE,
+            );
+            for waypoint in &route.waypoints {
+                gizmos.sphere(
+                    Isometry3d::from_translation(waypoint.position),
+                    0.05,
+                    css::SKY_BLUE,
+                );
+            }
+        }
+    }
+}
+
+fn sync_ai_debug_labels(
+    mut commands: Commands,
+    enemy_query: Query<Entity, With<Enemy>>,
+    label_query: Query<(Entity, &AiDebugLabel)>,
+) {
+    for (label_entity, label) in label_query.iter() {
+        if enemy_query.get(label.enemy).is_err() {
+            commands.entity(label_entity).despawn_recursive();
+        }
+    }
+
+    for enemy_entity in enemy_query.iter() {
+        let has_label = label_query
+            .iter()
+            .any(|(_, label)| label.enemy == enemy_entity);
+        if has_label {
+            continue;
+        }
+
+        commands.spawn((
+            Text::new(""),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Visibility::Hidden,
+            AiDebugLabel {
+                enemy: enemy_entity,
+            },
+        ));
+    }
+}
+
+fn update_ai_debug_labels(
+    settings: Res<AiDebugSettings>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<FollowCamera>>,
+    enemy_query: Query<(&Transform, &EnemyAi, &CombatStatus), With<Enemy>>,
+    mut label_query: Query<(
+        &AiDebugLabel,
+        &mut Text,
+        &mut TextColor,
+        &mut Node,
+        &mut Visibility,
+    )>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    for (label, mut text, mut text_color, mut node, mut visibility) in label_query.iter_mut() {
+        let screen_position = enemy_query
+            .get(label.enemy)
+            .ok()
+            .filter(|(_, _, combat_status)| settings.enabled && !combat_status.is_dead)
+            .and_then(|(transform, ai, _)| {
+                camera
+                    .world_to_viewport(
+                        camera_transform,
+                        transform.translation + Vec3::Y * LABEL_HEIGHT,
+                    )
+                    .ok()
+                    .map(|position| (position, ai))
+            });
+
+        let Some((position, ai)) = screen_position else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        *visibility = Visibility::Visible;
+        node.left = Val::Px(position.x);
+        node.top = Val::Px(position.y);
+        text.0 = format!("{:?} {:.1}s", ai.state, ai.state_timer);
+        text_color.0 = state_color(ai.state);
+    }
+}
+
+fn setup_ai_debug_panel(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 13.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(20.0),
+            top: Val::Px(20.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Visibility::Hidden,
+        AiDebugPanel,
+    ));
+}
+
+fn update_ai_debug_panel(
+    settings: Res<AiDebugSettings>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(Entity, &Transform, &EnemyAi, &Health, &Stamina), With<Enemy>>,
+    mut panel_query: Query<&mut Text, With<AiDebugPanel>>,
+) {
+    if !settings.enabled {
+        return;
+    }
+    let Ok(mut text) = panel_query.get_single_mut() else {
+        return;
+    };
+    let player_position = player_query.get_single().map(|t| t.translation).ok();
+
+    let mut panel = String::from("AI blackboard (F3)\n");
+    for (entity, transform, ai, health, stamina) in enemy_query.iter() {
+        let player_distance = player_position
+            .map(|position| format!("{:.2}", position.distance(transform.translation)))
+            .unwrap_or_else(|| "-".to_string());
+        let _ = writeln!(
+            panel,
+            "{entity}: {:?} t={:.1} hp={:.0}/{:.0} st={:.0} player={} home={:.2} token={} cd={:.1} angle={:.2} threat={:.2}",
+            ai.state,
+            ai.state_timer,
+            health.current,
+            health.max,
+            stamina.current,
+            player_distance,
+            ai.home_position.distance(transform.translation),
+            ai.has_attack_token,
+            ai.token_cooldown,
+            ai.circle_angle,
+            ai.threat_timer,
+        );
+    }
+    text.0 = panel;
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::gameplay::boss::{Boss, BossDefeated, BossEncounterReset, BossEncounterStarted};
+use crate::gameplay::health::Health;
+
+#[derive(Component)]
+pub struct BossHealthBar {
+    pub boss: Entity,
+}
+
+#[derive(Component)]
+pub struct BossHealthBarFill {
+    pub boss: Entity,
+}
+
+pub struct BossHealthBarPlugin;
+
+impl Plugin for BossHealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_boss_health_bar,
+                update_boss_health_bar,
+                despawn_boss_health_bar,
+            ),
+        );
+    }
+}
+
+fn spawn_boss_health_bar(
+    mut commands: Commands,
+    mut started_events: EventReader<BossEncounterStarted>,
+    boss_query: Query<&Boss>,
+    bar_query: Query<&BossHealthBar>,
+) {
+    for event in started_events.read() {
+        let Ok(boss) = boss_query.get(event.boss) else {
+            continue;
+        };
+        if bar_query.iter().any(|bar| bar.boss == event.boss) {
+            continue;
+        }
+
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(25.0),
+                    width: Val::Percent(50.0),
+                    bottom: Val::Px(40.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BossHealthBar { boss: event.boss },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text::new(boss.name.clone()),
+                    TextFont {
+                        font_size: 22.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+
+                parent
+                    .spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Px(16.0),
+                            margin: UiRect::top(Val::Px(5.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    ))
+                    .with_children(|bar_bg| {
+                        bar_bg.spawn((
+                            Node {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.6, 0.1, 0.1)),
+                            BossHealthBarFill { boss: event.boss },
+                        ));
+                    });
+            });
+    }
+}
+
+fn update_boss_health_bar(
+    boss_query: Query<&Health, With<Boss>>,
+    mut fill_query: Query<(&mut Node, &BossHealthBarFill)>,
+) {
+    for (mut bar_node, fill) in fill_query.iter_mut() {
+        let health_percent = boss_query
+            .get(fill.boss)
+            .map(|health| health.current / health.max * 100.0)
+            .unwrap_or(0.0);
+        bar_node.width = Val::Percent(health_percent.max(0.0));
+    }
+}
+
+/// The bar goes away once the boss is beaten, or when the player dies and the
+/// fight resets.
+fn despawn_boss_health_bar(
+    mut commands: Commands,
+    mut defeated_events: EventReader<BossDefeated>,
+    mut reset_events: EventReader<BossEncounterReset>,
+    bar_query: Query<(Entity, &BossHealthBar)>,
+) {
+    let bosses = defeated_events
+        .read()
+        .map(|event| event.boss)
+        .chain(reset_events.read().map(|event| event.boss));
+    for boss in bosses {
+        for (bar_entity, bar) in bar_query.iter() {
+            if bar.boss == boss {
+                commands.entity(bar_entity).despawn_recursive();
+            }
+        }
+    }
+}
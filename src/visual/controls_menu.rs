@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+
+use crate::core::bindings::{Action, Binding, InputBindings, InputDevices};
+use crate::states::AppState;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const BUTTON_WAITING_COLOR: Color = Color::srgb(0.6, 0.1, 0.1);
+
+#[derive(Component)]
+pub struct ControlsMenu;
+
+#[derive(Component, Clone, Copy)]
+pub enum ControlsButton {
+    Rebind(Action),
+    ResetDefaults,
+}
+
+/// Label on a rebind button showing the action's current keys.
+#[derive(Component)]
+pub struct BindingText(pub Action);
+
+/// The action waiting for its new key, if the player clicked one.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<Action>);
+
+pub struct ControlsMenuPlugin;
+
+impl Plugin for ControlsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(AppState::Paused), spawn_controls_menu)
+            .add_systems(OnExit(AppState::Paused), despawn_controls_menu)
+            .add_systems(
+                Update,
+                // Capture before handling clicks so the click that starts a rebind
+                // isn't taken as the new binding
+                (
+                    capture_rebind,
+                    handle_controls_buttons,
+                    update_controls_menu,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Paused)),
+            );
+    }
+}
+
+fn binding_label(action: Action, bindings: &InputBindings, waiting: bool) -> String {
+    if waiting {
+        return format!("{}: press a key or button...", action.name());
+    }
+    let names: Vec<String> = bindings
+        .get(action)
+        .iter()
+        .map(|binding| binding.name())
+        .collect();
+    let keys = if names.is_empty() {
+        "unbound".to_string()
+    } else {
+        names.join(" / ")
+    };
+    format!("{}: {}", action.name(), keys)
+}
+
+fn spawn_controls_button(
+    parent: &mut ChildBuilder,
+    label: String,
+    button: ControlsButton,
+    text: Option<BindingText>,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                margin: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            button,
+        ))
+        .with_children(|button| {
+            let mut label = button.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            if let Some(text) = text {
+                label.insert(text);
+            }
+        });
+}
+
+fn spawn_controls_menu(mut commands: Commands, bindings: Res<InputBindings>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                top: Val::Px(20.0),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            ControlsMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Controls (click to rebind)"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            for action in Action::ALL {
+                spawn_controls_button(
+                    parent,
+                    binding_label(action, &bindings, false),
+                    ControlsButton::Rebind(action),
+                    Some(BindingText(action)),
+                );
+            }
+
+            spawn_controls_button(
+                parent,
+                "Reset to defaults".to_string(),
+                ControlsButton::ResetDefaults,
+                None,
+            );
+        });
+}
+
+fn despawn_controls_menu(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    menu_query: Query<Entity, With<ControlsMenu>>,
+) {
+    rebinding.0 = None;
+    for menu in menu_query.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn capture_rebind(
+    devices: InputDevices,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let Some(binding) = Binding::first_just_pressed(&devices) else {
+        return;
+    };
+
+    info!("Bound {} to {}", action.name(), binding.name());
+    bindings.rebind(action, binding);
+    bindings.save();
+    rebinding.0 = None;
+}
+
+fn handle_controls_buttons(
+    button_query: Query<(&Interaction, &ControlsButton), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            ControlsButton::Rebind(action) => rebinding.0 = Some(action),
+            ControlsButton::ResetDefaults => {
+                *bindings = InputBindings::reset();
+                rebinding.0 = None;
+            }
+        }
+    }
+}
+
+fn update_controls_menu(
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+    mut text_query: Query<(&mut Text, &BindingText)>,
+    mut button_query: Query<(&Interaction, &ControlsButton, &mut BackgroundColor)>,
+) {
+    if bindings.is_changed() || rebinding.is_changed() {
+        for (mut text, binding_text) in text_query.iter_mut() {
+            let action = binding_text.0;
+            text.0 = binding_label(action, &bindings, rebinding.0 == Some(action));
+        }
+    }
+
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        let waiting =
+            matches!(button, ControlsButton::Rebind(action) if rebinding.0 == Some(*action));
+        background.0 = match interaction {
+            _ if waiting => BUTTON_WAITING_COLOR,
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
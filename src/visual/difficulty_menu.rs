@@ -0,0 +1,273 @@
+use bevy::prelude::*;
+use bevy::window::CursorGrabMode;
+
+use crate::gameplay::difficulty::{Difficulty, DifficultySettings};
+use crate::states::AppState;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const BUTTON_SELECTED_COLOR: Color = Color::srgb(0.6, 0.1, 0.1);
+
+#[derive(Component)]
+pub struct DifficultyMenu;
+
+/// Text line showing the current value of one multiplier.
+#[derive(Component)]
+pub struct DifficultyValueText(pub DifficultyKnob);
+
+#[derive(Component, Clone, Copy)]
+pub enum DifficultyButton {
+    Preset(Difficulty),
+    Adjust(DifficultyKnob, f32),
+    Resume,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DifficultyKnob {
+    DamageDealt,
+    DamageTaken,
+    EnemyHealth,
+    ReactionTime,
+    MaxAttackers,
+}
+
+impl DifficultyKnob {
+    const ALL: [DifficultyKnob; 5] = [
+        DifficultyKnob::DamageDealt,
+        DifficultyKnob::DamageTaken,
+        DifficultyKnob::EnemyHealth,
+        DifficultyKnob::ReactionTime,
+        DifficultyKnob::MaxAttackers,
+    ];
+
+    fn step(self) -> f32 {
+        match self {
+            DifficultyKnob::MaxAttackers => 1.0,
+            _ => 0.25,
+        }
+    }
+
+    fn label(self, settings: &DifficultySettings) -> String {
+        match self {
+            DifficultyKnob::DamageDealt => format!("Damage dealt  x{:.2}", settings.damage_dealt),
+            DifficultyKnob::DamageTaken => format!("Damage taken  x{:.2}", settings.damage_taken),
+            DifficultyKnob::EnemyHealth => format!("Enemy health  x{:.2}", settings.enemy_health),
+            DifficultyKnob::ReactionTime => {
+                format!("Reaction time x{:.2}", settings.reaction_time)
+            }
+            DifficultyKnob::MaxAttackers => format!("Max attackers {}", settings.max_attackers),
+        }
+    }
+
+    fn adjust(self, settings: &mut DifficultySettings, delta: f32) {
+        let adjust_multiplier = |value: &mut f32| *value = (*value + delta).clamp(0.25, 4.0);
+        match self {
+            DifficultyKnob::DamageDealt => adjust_multiplier(&mut settings.damage_dealt),
+            DifficultyKnob::DamageTaken => adjust_multiplier(&mut settings.damage_taken),
+            DifficultyKnob::EnemyHealth => adjust_multiplier(&mut settings.enemy_health),
+            DifficultyKnob::ReactionTime => adjust_multiplier(&mut settings.reaction_time),
+            DifficultyKnob::MaxAttackers => {
+                settings.max_attackers =
+                    (settings.max_attackers as f32 + delta).clamp(1.0, 8.0) as usize;
+            }
+        }
+        settings.preset = Difficulty::Custom;
+    }
+}
+
+pub struct DifficultyMenuPlugin;
+
+impl Plugin for DifficultyMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, toggle_pause)
+            .add_systems(
+                OnEnter(AppState::Paused),
+                (spawn_difficulty_menu, release_cursor),
+            )
+            .add_systems(
+                OnExit(AppState::Paused),
+                (despawn_difficulty_menu, grab_cursor),
+            )
+            .add_systems(
+                Update,
+                (handle_difficulty_buttons, update_difficulty_menu)
+                    .chain()
+                    .run_if(in_state(AppState::Paused)),
+            );
+    }
+}
+
+fn toggle_pause(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let start_pressed = gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::Start));
+    if !keyboard.just_pressed(KeyCode::Escape) && !start_pressed {
+        return;
+    }
+
+    match state.get() {
+        AppState::Playing => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::Playing),
+        _ => {}
+    }
+}
+
+pub fn release_cursor(mut windows: Query<&mut Window>) {
+    if let Ok(mut window) = windows.get_single_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::None;
+        window.cursor_options.visible = true;
+    }
+}
+
+pub fn grab_cursor(mut windows: Query<&mut Window>) {
+    if let Ok(mut window) = windows.get_single_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::Locked;
+        window.cursor_options.visible = false;
+    }
+}
+
+fn spawn_button(parent: &mut ChildBuilder, label: &str, button: DifficultyButton) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                margin: UiRect::all(Val::Px(4.0)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            button,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn spawn_difficulty_menu(mut commands: Commands, settings: Res<DifficultySettings>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            DifficultyMenu,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 36.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent
+                .spawn(Node {
+                    margin: UiRect::vertical(Val::Px(12.0)),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for preset in Difficulty::PRESETS {
+                        spawn_button(
+                            row,
+                            &format!("{:?}", preset),
+                            DifficultyButton::Preset(preset),
+                        );
+                    }
+                });
+
+            for knob in DifficultyKnob::ALL {
+                parent
+                    .spawn(Node {
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        spawn_button(row, "-", DifficultyButton::Adjust(knob, -knob.step()));
+                        row.spawn((
+                            Text::new(knob.label(&settings)),
+                            TextFont {
+                                font_size: 18.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                            Node {
+                                width: Val::Px(200.0),
+                                ..default()
+                            },
+                            DifficultyValueText(knob),
+                        ));
+                        spawn_button(row, "+", DifficultyButton::Adjust(knob, knob.step()));
+                    });
+            }
+
+            spawn_button(parent, "Resume", DifficultyButton::Resume);
+        });
+}
+
+fn despawn_difficulty_menu(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<DifficultyMenu>>,
+) {
+    for menu in menu_query.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn handle_difficulty_buttons(
+    button_query: Query<(&Interaction, &DifficultyButton), Changed<Interaction>>,
+    mut settings: ResMut<DifficultySettings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            DifficultyButton::Preset(preset) => *settings = DifficultySettings::preset(preset),
+            DifficultyButton::Adjust(knob, delta) => knob.adjust(&mut settings, delta),
+            DifficultyButton::Resume => next_state.set(AppState::Playing),
+        }
+    }
+}
+
+fn update_difficulty_menu(
+    settings: Res<DifficultySettings>,
+    mut text_query: Query<(&mut Text, &DifficultyValueText)>,
+    mut button_query: Query<(&Interaction, &DifficultyButton, &mut BackgroundColor)>,
+) {
+    if settings.is_changed() {
+        for (mut text, value_text) in text_query.iter_mut() {
+            text.0 = value_text.0.label(&settings);
+        }
+    }
+
+    for (interaction, button, mut background) in button_query.iter_mut() {
+        let selected =
+            matches!(button, DifficultyButton::Preset(preset) if *preset == settings.preset);
+        background.0 = match interaction {
+            _ if selected => BUTTON_SELECTED_COLOR,
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+use super::difficulty_menu::{grab_cursor, release_cursor};
+use crate::core::input::{read_input, PlayerInput, PlayerInputSet};
+use crate::gameplay::respawn::{ActiveCheckpoint, RespawnSettings};
+use crate::states::AppState;
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const BUTTON_HOVER_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[derive(Component)]
+pub struct ResetEnemiesText;
+
+#[derive(Component, Clone, Copy)]
+pub enum GameOverButton {
+    Respawn,
+    ToggleResetEnemies,
+}
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            (spawn_game_over_screen, release_cursor),
+        )
+        .add_systems(
+            OnExit(AppState::GameOver),
+            (despawn_game_over_screen, grab_cursor),
+        )
+        .add_systems(
+            PreUpdate,
+            read_game_over_clicks
+                .in_set(PlayerInputSet)
+                .after(read_input)
+                .after(UiSystem::Focus)
+                .run_if(in_state(AppState::GameOver)),
+        )
+        .add_systems(
+            Update,
+            (handle_game_over_input, update_game_over_screen)
+                .chain()
+                .run_if(in_state(AppState::GameOver)),
+        );
+    }
+}
+
+fn reset_enemies_label(settings: &RespawnSettings) -> String {
+    let state = if settings.reset_enemies { "On" } else { "Off" };
+    format!("Reset enemies: {state}")
+}
+
+fn spawn_button(parent: &mut ChildBuilder, label: &str, button: GameOverButton) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                margin: UiRect::all(Val::Px(4.0)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            button,
+        ))
+        .with_children(|node| {
+            let mut text = node.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            if matches!(button, GameOverButton::ToggleResetEnemies) {
+                text.insert(ResetEnemiesText);
+            }
+        });
+}
+
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    settings: Res<RespawnSettings>,
+    checkpoint: Res<ActiveCheckpoint>,
+) {
+    let respawn_label = if checkpoint.checkpoint.is_some() {
+        "Respawn at checkpoint"
+    } else {
+        "Respawn at start"
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.2, 0.0, 0.0, 0.6)),
+            GameOverScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("You Died"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(16.0)),
+                    ..default()
+                },
+            ));
+
+            spawn_button(parent, respawn_label, GameOverButton::Respawn);
+            spawn_button(
+                parent,
+                &reset_enemies_label(&settings),
+                GameOverButton::ToggleResetEnemies,
+            );
+
+            parent.spawn((
+                Text::new("Enter / A to respawn, R / X to toggle enemy reset"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                Node {
+                    margin: UiRect::top(Val::Px(12.0)),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_game_over_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<GameOverScreen>>,
+) {
+    for screen in screen_query.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+/// Clicks on the buttons count as the menu buttons they stand for, so
+/// `handle_game_over_input` (and replays) only have to look at `PlayerInput`.
+fn read_game_over_clicks(
+    button_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    mut input: ResMut<PlayerInput>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            GameOverButton::Respawn => input.menu_confirm = true,
+            GameOverButton::ToggleResetEnemies => input.menu_alternate = true,
+        }
+    }
+}
+
+/// Confirm respawns; the alternate button toggles whether enemies reset.
+fn handle_game_over_input(
+    input: Res<PlayerInput>,
+    mut settings: ResMut<RespawnSettings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if input.menu_alternate {
+        settings.reset_enemies = !settings.reset_enemies;
+    }
+    if input.menu_confirm {
+        next_state.set(AppState::Playing);
+    }
+}
+
+fn update_game_over_screen(
+    settings: Res<RespawnSettings>,
+    mut text_query: Query<&mut Text, With<ResetEnemiesText>>,
+    mut button_query: Query<(&Interaction, &mut BackgroundColor), With<GameOverButton>>,
+) {
+    if settings.is_changed() {
+        for mut text in text_query.iter_mut() {
+            text.0 = reset_enemies_label(&settings);
+        }
+    }
+
+    for (interaction, mut background) in button_query.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVER_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::core::camera::FollowCamera;
 use crate::gameplay::ai::Enemy;
+use crate::gameplay::boss::Boss;
 use crate::gameplay::combat::Stamina;
 use crate::gameplay::health::Health;
 use crate::gameplay::player::Player;
@@ -153,7 +154,8 @@ fn spawn_enemy_health_bars(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    enemies: Query<Entity, (With<Enemy>, Without<EnemyHealthBar>)>,
+    // Bosses get a dedicated HUD bar instead (see boss_health_bar.rs)
+    enemies: Query<Entity, (With<Enemy>, Without<Boss>, Without<EnemyHealthBar>)>,
     health_bars: Query<&EnemyHealthBar>,
 ) {
     for enemy_entity in enemies.iter() {
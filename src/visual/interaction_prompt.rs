@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+use crate::core::bindings::{Action, InputBindings};
+use crate::gameplay::interaction::{Interactable, InteractionFocus};
+
+#[derive(Component)]
+pub struct InteractionPrompt;
+
+pub struct InteractionPromptPlugin;
+
+impl Plugin for InteractionPromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_interaction_prompt)
+            .add_systems(Update, update_interaction_prompt);
+    }
+}
+
+fn setup_interaction_prompt(mut commands: Commands) {
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            bottom: Val::Px(120.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                Visibility::Hidden,
+                InteractionPrompt,
+            ));
+        });
+}
+
+/// Shows "[key] prompt" for the focused interactable.
+fn update_interaction_prompt(
+    focus: Res<InteractionFocus>,
+    bindings: Res<InputBindings>,
+    interactable_query: Query<Ref<Interactable>>,
+    mut prompt_query: Query<(&mut Text, &mut Visibility), With<InteractionPrompt>>,
+) {
+    let Ok((mut text, mut visibility)) = prompt_query.get_single_mut() else {
+        return;
+    };
+
+    let focused = focus
+        .0
+        .and_then(|entity| interactable_query.get(entity).ok());
+    let Some(interactable) = focused else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    if !focus.is_changed() && !bindings.is_changed() && !interactable.is_changed() {
+        return;
+    }
+
+    let keys = bindings
+        .get(Action::Interact)
+        .iter()
+        .map(|binding| binding.name())
+        .collect::<Vec<_>>()
+        .join(" / ");
+    text.0 = format!("[{keys}] {}", interactable.prompt);
+    *visibility = Visibility::Inherited;
+}
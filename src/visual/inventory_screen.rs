@@ -0,0 +1,334 @@
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+use super::difficulty_menu::{grab_cursor, release_cursor};
+use crate::core::input::{read_input, PlayerInput, PlayerInputSet};
+use crate::gameplay::combat::CombatStatus;
+use crate::gameplay::inventory::{Inventory, InventoryChanged, ItemDatabase, ItemKind, UseItem};
+use crate::gameplay::player::Player;
+use crate::states::AppState;
+
+const SLOT_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const SLOT_SELECTED_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+
+#[derive(Component)]
+pub struct InventoryScreen;
+
+/// Parent of the slot buttons; its children are rebuilt whenever the inventory changes.
+#[derive(Component)]
+pub struct InventorySlotList;
+
+#[derive(Component)]
+pub struct InventorySlot {
+    pub index: usize,
+    pub item: String,
+}
+
+#[derive(Component)]
+pub struct InventoryDetails;
+
+/// Slot highlighted by the keyboard, gamepad or mouse hover.
+#[derive(Resource, Default)]
+pub struct InventorySelection(pub usize);
+
+pub struct InventoryScreenPlugin;
+
+impl Plugin for InventoryScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InventorySelection>()
+            .add_systems(
+                OnEnter(AppState::Inventory),
+                (spawn_inventory_screen, release_cursor),
+            )
+            .add_systems(
+                OnExit(AppState::Inventory),
+                (despawn_inventory_screen, grab_cursor),
+            )
+            .add_systems(
+                PreUpdate,
+                read_inventory_clicks
+                    .in_set(PlayerInputSet)
+                    .after(read_input)
+                    .after(UiSystem::Focus)
+                    .run_if(in_state(AppState::Inventory)),
+            )
+            .add_systems(
+                Update,
+                toggle_inventory
+                    .run_if(in_state(AppState::Playing).or(in_state(AppState::Inventory))),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_inventory_input,
+                    rebuild_inventory_slots,
+                    update_inventory_screen,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Inventory)),
+            );
+    }
+}
+
+/// The Inventory action opens and closes the screen; menu back also closes it.
+fn toggle_inventory(
+    input: Res<PlayerInput>,
+    state: Res<State<AppState>>,
+    player_query: Query<&CombatStatus, With<Player>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    match state.get() {
+        AppState::Playing if input.inventory => {
+            if player_query
+                .get_single()
+                .is_ok_and(|combat_status| !combat_status.is_dead)
+            {
+                next_state.set(AppState::Inventory);
+            }
+        }
+        AppState::Inventory if input.inventory || input.menu_back => {
+            next_state.set(AppState::Playing);
+        }
+        _ => {}
+    }
+}
+
+fn spawn_inventory_screen(mut commands: Commands, mut selection: ResMut<InventorySelection>) {
+    selection.0 = 0;
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            InventoryScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Inventory"),
+                TextFont {
+                    font_size: 36.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::bottom(Val::Px(12.0)),
+                    ..default()
+                },
+            ));
+
+            parent.spawn((
+                Node {
+                    width: Val::Px(320.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                InventorySlotList,
+            ));
+
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.85, 0.85, 0.85)),
+                Node {
+                    width: Val::Px(320.0),
+                    margin: UiRect::top(Val::Px(12.0)),
+                    ..default()
+                },
+                InventoryDetails,
+            ));
+
+            parent.spawn((
+                Text::new("Click / Enter / A to use or equip, I / Esc to close"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                Node {
+                    margin: UiRect::top(Val::Px(12.0)),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_inventory_screen(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<InventoryScreen>>,
+) {
+    for screen in screen_query.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+/// Hovering over a slot points at it; clicking it also confirms, so
+/// `handle_inventory_input` (and replays) only have to look at `PlayerInput`.
+fn read_inventory_clicks(
+    slot_query: Query<(&Interaction, &InventorySlot), Changed<Interaction>>,
+    mut input: ResMut<PlayerInput>,
+) {
+    for (interaction, slot) in slot_query.iter() {
+        match interaction {
+            Interaction::Pressed => {
+                input.menu_pointed = Some(slot.index);
+                input.menu_confirm = true;
+            }
+            Interaction::Hovered => input.menu_pointed = Some(slot.index),
+            Interaction::None => {}
+        }
+    }
+}
+
+/// Pointing at a slot selects it, up / down move the selection and confirm
+/// uses the selected item.
+fn handle_inventory_input(
+    input: Res<PlayerInput>,
+    player_query: Query<(Entity, &Inventory), With<Player>>,
+    mut selection: ResMut<InventorySelection>,
+    mut use_events: EventWriter<UseItem>,
+) {
+    let Ok((player_entity, inventory)) = player_query.get_single() else {
+        return;
+    };
+    let slots = inventory.stacks.len();
+    if slots == 0 {
+        return;
+    }
+    if let Some(index) = input.menu_pointed {
+        selection.0 = index;
+    }
+    if input.menu_up {
+        selection.0 = (selection.0 + slots - 1) % slots;
+    }
+    if input.menu_down {
+        selection.0 = (selection.0 + 1) % slots;
+    }
+    selection.0 = selection.0.min(slots - 1);
+    if input.menu_confirm {
+        use_events.send(UseItem {
+            owner: player_entity,
+            item: inventory.stacks[selection.0].item.clone(),
+        });
+    }
+}
+
+fn slot_label(items: &ItemDatabase, inventory: &Inventory, index: usize) -> String {
+    let stack = &inventory.stacks[index];
+    let Some(item) = items.get(&stack.item) else {
+        return stack.item.clone();
+    };
+    let mut label = match item.kind {
+        ItemKind::Flask { charges, .. } => {
+            format!("{} ({}/{charges})", item.name, inventory.charges(&item.id))
+        }
+        _ if stack.count > 1 => format!("{} x{}", item.name, stack.count),
+        _ => item.name.clone(),
+    };
+    if inventory.equipped_weapon.as_deref() == Some(stack.item.as_str()) {
+        label.push_str(" (equipped)");
+    }
+    label
+}
+
+/// Respawns the slot buttons when the screen opens or the player's inventory changes.
+fn rebuild_inventory_slots(
+    mut commands: Commands,
+    items: Res<ItemDatabase>,
+    mut changed_events: EventReader<InventoryChanged>,
+    player_query: Query<(Entity, &Inventory), With<Player>>,
+    list_query: Query<(Entity, Ref<InventorySlotList>)>,
+) {
+    let Ok((player_entity, inventory)) = player_query.get_single() else {
+        return;
+    };
+    let Ok((list_entity, list)) = list_query.get_single() else {
+        return;
+    };
+    let player_changed = changed_events
+        .read()
+        .any(|event| event.owner == player_entity);
+    if !player_changed && !list.is_added() {
+        return;
+    }
+
+    let mut list_commands = commands.entity(list_entity);
+    list_commands.despawn_descendants();
+    list_commands.with_children(|parent| {
+        if inventory.stacks.is_empty() {
+            parent.spawn((
+                Text::new("Empty"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.6, 0.6)),
+            ));
+        }
+        for (index, stack) in inventory.stacks.iter().enumerate() {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                        margin: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(SLOT_COLOR),
+                    InventorySlot {
+                        index,
+                        item: stack.item.clone(),
+                    },
+                ))
+                .with_children(|slot| {
+                    slot.spawn((
+                        Text::new(slot_label(&items, inventory, index)),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        }
+    });
+}
+
+/// Highlights the selected slot and shows its description.
+fn update_inventory_screen(
+    items: Res<ItemDatabase>,
+    selection: Res<InventorySelection>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut slot_query: Query<(&InventorySlot, &mut BackgroundColor)>,
+    mut details_query: Query<&mut Text, With<InventoryDetails>>,
+) {
+    for (slot, mut background) in slot_query.iter_mut() {
+        background.0 = if slot.index == selection.0 {
+            SLOT_SELECTED_COLOR
+        } else {
+            SLOT_COLOR
+        };
+    }
+
+    let Ok(mut details) = details_query.get_single_mut() else {
+        return;
+    };
+    let description = player_query
+        .get_single()
+        .ok()
+        .and_then(|inventory| inventory.stacks.get(selection.0))
+        .and_then(|stack| items.get(&stack.item))
+        .map_or("", |item| item.description.as_str());
+    if details.0 != description {
+        details.0 = description.to_string();
+    }
+}
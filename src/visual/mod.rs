@@ -1,5 +1,19 @@
+pub mod ai_debug;
+pub mod boss_health_bar;
+pub mod controls_menu;
+pub mod difficulty_menu;
+pub mod game_over;
 pub mod health_bar;
+pub mod interaction_prompt;
+pub mod inventory_screen;
 pub mod particles;
 
+pub use ai_debug::AiDebugPlugin;
+pub use boss_health_bar::BossHealthBarPlugin;
+pub use controls_menu::ControlsMenuPlugin;
+pub use difficulty_menu::DifficultyMenuPlugin;
+pub use game_over::GameOverPlugin;
 pub use health_bar::HealthBarPlugin;
+pub use interaction_prompt::InteractionPromptPlugin;
+pub use inventory_screen::InventoryScreenPlugin;
 pub use particles::ParticlePlugin;
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use crate::core::rng::{GameRng, RngStream};
 use crate::gameplay::combat::HitEvent;
 
 #[derive(Component)]
@@ -21,6 +22,7 @@ fn spawn_impact_sparks(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut hit_events: EventReader<HitEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     for event in hit_events.read() {
         let spark_count = if event.blocked { 15 } else { 8 };
@@ -31,9 +33,9 @@ fn spawn_impact_sparks(
         };
 
         for _ in 0..spark_count {
-            let angle = rand::random::<f32>() * std::f32::consts::TAU;
-            let elevation = rand::random::<f32>() * 0.5 + 0.3;
-            let speed = rand::random::<f32>() * 3.0 + 2.0;
+            let angle = rng.unit(RngStream::Particles) * std::f32::consts::TAU;
+            let elevation = rng.unit(RngStream::Particles) * 0.5 + 0.3;
+            let speed = rng.unit(RngStream::Particles) * 3.0 + 2.0;
 
             let velocity = Vec3::new(
                 angle.cos() * speed * (1.0 - elevation),